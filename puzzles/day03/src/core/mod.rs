@@ -1,2 +1,5 @@
 pub mod rucksack;
 pub use rucksack::*;
+
+pub mod reorganize;
+pub use reorganize::*;
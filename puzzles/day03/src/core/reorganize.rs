@@ -0,0 +1,426 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::{Priority, Rucksack, MAX_PRIORITY};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compartment {
+    First,
+    Second,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Relocation {
+    Swap { first: Priority, second: Priority },
+    Move { priority: Priority, to: Compartment },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub shared: Vec<Priority>,
+    pub relocations: Vec<Relocation>,
+}
+
+impl Plan {
+    pub fn is_empty(&self) -> bool {
+        self.relocations.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.relocations.len()
+    }
+
+    pub fn apply(&self, rucksack: &[Priority]) -> (Rucksack, Rucksack) {
+        let (first, second) = compartments(rucksack);
+        let (mut first, mut second) = (first.to_vec(), second.to_vec());
+
+        fn take(pocket: &mut Rucksack, priority: Priority) {
+            let i = pocket
+                .iter()
+                .position(|&p| p == priority)
+                .expect("relocated item should be in its compartment");
+            pocket.swap_remove(i);
+        }
+
+        for relocation in self.relocations.iter() {
+            match *relocation {
+                Relocation::Swap {
+                    first: a,
+                    second: b,
+                } => {
+                    take(&mut first, a);
+                    take(&mut second, b);
+                    first.push(b);
+                    second.push(a);
+                }
+                Relocation::Move {
+                    priority,
+                    to: Compartment::First,
+                } => {
+                    take(&mut second, priority);
+                    first.push(priority);
+                }
+                Relocation::Move {
+                    priority,
+                    to: Compartment::Second,
+                } => {
+                    take(&mut first, priority);
+                    second.push(priority);
+                }
+            }
+        }
+
+        (first, second)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BadgeViolation {
+    Badges {
+        group: usize,
+        lines: Vec<usize>,
+        badges: Vec<Priority>,
+    },
+    IncompleteGroup {
+        group: usize,
+        lines: Vec<usize>,
+    },
+}
+
+impl fmt::Display for BadgeViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (Self::Badges { group, lines, .. } | Self::IncompleteGroup { group, lines }) = self;
+
+        let lines = lines
+            .iter()
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        write!(f, "group number {} (lines {}): ", group, lines)?;
+
+        match self {
+            Self::Badges { badges, .. } if badges.is_empty() => write!(f, "no shared badge"),
+            Self::Badges { badges, .. } => {
+                let badges = badges.iter().filter_map(|&p| item(p)).collect::<String>();
+                write!(f, "expected one shared badge, found {:?}", badges)
+            }
+            Self::IncompleteGroup { .. } => write!(f, "incomplete group"),
+        }
+    }
+}
+
+pub fn item(priority: Priority) -> Option<char> {
+    match priority {
+        1..=26 => Some((b'a' + (priority - 1) as u8) as char),
+        27..=MAX_PRIORITY => Some((b'A' + (priority - 27) as u8) as char),
+        _ => None,
+    }
+}
+
+pub fn compartments(rucksack: &[Priority]) -> (&[Priority], &[Priority]) {
+    rucksack.split_at(rucksack.len() / 2)
+}
+
+fn count(pocket: &[Priority]) -> BTreeMap<Priority, usize> {
+    let mut counts = BTreeMap::new();
+    for &priority in pocket {
+        *counts.entry(priority).or_default() += 1;
+    }
+    counts
+}
+
+pub fn find_shared(rucksack: &[Priority]) -> Vec<Priority> {
+    let (first, second) = compartments(rucksack);
+    let second = count(second);
+    count(first)
+        .into_keys()
+        .filter(|priority| second.contains_key(priority))
+        .collect()
+}
+
+pub fn plan(rucksack: &[Priority]) -> Plan {
+    let (first, second) = compartments(rucksack);
+    let (first, second) = (count(first), count(second));
+
+    // Each shared item has every copy gathered into one compartment, either by sending the first
+    // compartment's copies to the second (`to_second`) or the other way around (`to_first`).
+    // Opposing relocations pair up as swaps, so the number of operations is the larger of the two
+    // totals. Choose directions to minimize that, then the number of items relocated.
+    let shared = first
+        .iter()
+        .filter_map(|(&priority, &a)| second.get(&priority).map(|&b| (priority, a, b)))
+        .collect::<Vec<_>>();
+
+    let capacity = shared.iter().map(|&(_, a, _)| a).sum::<usize>() + 1;
+
+    // best[x] holds the least `to_first` total (and the choices) for a `to_second` total of x.
+    let mut best: Vec<Option<(usize, Vec<Compartment>)>> = vec![None; capacity];
+    best[0] = Some((0, Vec::new()));
+
+    for &(_, a, b) in shared.iter() {
+        let mut next: Vec<Option<(usize, Vec<Compartment>)>> = vec![None; capacity];
+
+        for (x, state) in best.iter().enumerate() {
+            let Some((y, choices)) = state else {
+                continue;
+            };
+
+            for (x, y, to) in [
+                (x + a, *y, Compartment::Second),
+                (x, y + b, Compartment::First),
+            ] {
+                if next[x].as_ref().is_none_or(|(other, _)| y < *other) {
+                    let mut choices = choices.clone();
+                    choices.push(to);
+                    next[x] = Some((y, choices));
+                }
+            }
+        }
+
+        best = next;
+    }
+
+    let (_, _, choices) = best
+        .into_iter()
+        .enumerate()
+        .filter_map(|(x, state)| state.map(|(y, choices)| (x, y, choices)))
+        .min_by_key(|&(x, y, _)| (x.max(y), x + y))
+        .expect("the empty choice is always possible");
+
+    let mut to_second = Vec::new();
+    let mut to_first = Vec::new();
+
+    for (&(priority, a, b), to) in shared.iter().zip(choices) {
+        match to {
+            Compartment::Second => to_second.extend(std::iter::repeat_n(priority, a)),
+            Compartment::First => to_first.extend(std::iter::repeat_n(priority, b)),
+        }
+    }
+
+    let swaps = to_second.len().min(to_first.len());
+
+    let mut relocations = to_second
+        .iter()
+        .zip(to_first.iter())
+        .map(|(&first, &second)| Relocation::Swap { first, second })
+        .collect::<Vec<_>>();
+
+    relocations.extend(to_second[swaps..].iter().map(|&priority| Relocation::Move {
+        priority,
+        to: Compartment::Second,
+    }));
+
+    relocations.extend(to_first[swaps..].iter().map(|&priority| Relocation::Move {
+        priority,
+        to: Compartment::First,
+    }));
+
+    Plan {
+        shared: shared
+            .into_iter()
+            .map(|(priority, _, _)| priority)
+            .collect(),
+        relocations,
+    }
+}
+
+pub fn find_badges(group: &[Rucksack]) -> Vec<Priority> {
+    let mut counts = [0; MAX_PRIORITY];
+
+    for rucksack in group {
+        let mut checklist = [false; MAX_PRIORITY];
+
+        for priority in rucksack {
+            checklist[priority - 1] = true;
+        }
+
+        for (count, checked) in counts.iter_mut().zip(checklist) {
+            if checked {
+                *count += 1;
+            }
+        }
+    }
+
+    counts
+        .iter()
+        .enumerate()
+        .filter(|(_, &count)| count == group.len())
+        .map(|(i, _)| i + 1)
+        .collect()
+}
+
+pub fn verify_badges(rucksacks: &[Rucksack], n: usize) -> Vec<BadgeViolation> {
+    rucksacks
+        .chunks(n)
+        .enumerate()
+        .filter_map(|(i, group)| {
+            let lines = (i * n + 1..=i * n + group.len()).collect();
+
+            if group.len() < n {
+                return Some(BadgeViolation::IncompleteGroup {
+                    group: i + 1,
+                    lines,
+                });
+            }
+
+            let badges = find_badges(group);
+
+            (badges.len() != 1).then_some(BadgeViolation::Badges {
+                group: i + 1,
+                lines,
+                badges,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rucksack(s: &str) -> Rucksack {
+        s.chars()
+            .map(|c| match c {
+                'a'..='z' => c as usize - 'a' as usize + 1,
+                _ => c as usize - 'A' as usize + 27,
+            })
+            .collect()
+    }
+
+    fn item_of(c: char) -> Priority {
+        rucksack(&c.to_string())[0]
+    }
+
+    fn assert_disjoint(rucksack: &[Priority], plan: &Plan) {
+        let (first, second) = plan.apply(rucksack);
+        assert!(first.iter().all(|priority| !second.contains(priority)));
+    }
+
+    #[test]
+    fn test_item() {
+        assert_eq!(item(1), Some('a'));
+        assert_eq!(item(26), Some('z'));
+        assert_eq!(item(27), Some('A'));
+        assert_eq!(item(52), Some('Z'));
+        assert_eq!(item(0), None);
+        assert_eq!(item(53), None);
+    }
+
+    #[test]
+    fn test_find_shared() {
+        assert_eq!(
+            find_shared(&rucksack("vJrwpWtwJgWrhcsFMMfFFhFp")),
+            [item_of('p')]
+        );
+        assert_eq!(find_shared(&rucksack("abcdef")), []);
+        assert_eq!(
+            find_shared(&rucksack("abcabc")),
+            [item_of('a'), item_of('b'), item_of('c')]
+        );
+    }
+
+    #[test]
+    fn test_plan_nothing_shared() {
+        let rucksack = rucksack("abcdef");
+        let plan = plan(&rucksack);
+        assert!(plan.is_empty());
+        assert_disjoint(&rucksack, &plan);
+    }
+
+    #[test]
+    fn test_plan_single_move() {
+        let rucksack = rucksack("vJrwpWtwJgWrhcsFMMfFFhFp");
+        let plan = plan(&rucksack);
+        assert_eq!(plan.len(), 1);
+        assert_disjoint(&rucksack, &plan);
+    }
+
+    #[test]
+    fn test_plan_prefers_swaps() {
+        let rucksack = rucksack("abxbac");
+        let plan = plan(&rucksack);
+        assert_eq!(
+            plan.relocations,
+            [Relocation::Swap {
+                first: item_of('b'),
+                second: item_of('a'),
+            }]
+        );
+        assert_disjoint(&rucksack, &plan);
+    }
+
+    #[test]
+    fn test_plan_moves_fewest_copies() {
+        let rucksack = rucksack("aaaxya");
+        let plan = plan(&rucksack);
+        assert_eq!(
+            plan.relocations,
+            [Relocation::Move {
+                priority: item_of('a'),
+                to: Compartment::First,
+            }]
+        );
+        assert_disjoint(&rucksack, &plan);
+    }
+
+    #[test]
+    fn test_plan_input() {
+        for line in include_str!("../../input").lines() {
+            let rucksack = rucksack(line);
+            let plan = plan(&rucksack);
+            assert_eq!(plan.shared, find_shared(&rucksack));
+            assert_disjoint(&rucksack, &plan);
+        }
+    }
+
+    #[test]
+    fn test_verify_badges() {
+        let rucksacks = include_str!("../../input-test")
+            .lines()
+            .map(rucksack)
+            .collect::<Vec<_>>();
+
+        assert_eq!(verify_badges(&rucksacks, 3), []);
+
+        let rucksacks = ["abc", "abd", "abe", "xyz", "qrs", "tuv", "xq"]
+            .into_iter()
+            .map(rucksack)
+            .collect::<Vec<_>>();
+
+        let violations = verify_badges(&rucksacks, 3);
+
+        assert_eq!(
+            violations,
+            [
+                BadgeViolation::Badges {
+                    group: 1,
+                    lines: vec![1, 2, 3],
+                    badges: vec![item_of('a'), item_of('b')],
+                },
+                BadgeViolation::Badges {
+                    group: 2,
+                    lines: vec![4, 5, 6],
+                    badges: vec![],
+                },
+                BadgeViolation::IncompleteGroup {
+                    group: 3,
+                    lines: vec![7],
+                },
+            ]
+        );
+
+        assert_eq!(
+            violations[0].to_string(),
+            "group number 1 (lines 1, 2, 3): expected one shared badge, found \"ab\""
+        );
+        assert_eq!(
+            violations[1].to_string(),
+            "group number 2 (lines 4, 5, 6): no shared badge"
+        );
+        assert_eq!(
+            violations[2].to_string(),
+            "group number 3 (lines 7): incomplete group"
+        );
+    }
+}
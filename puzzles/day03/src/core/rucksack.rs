@@ -4,7 +4,7 @@ pub type Priority = usize;
 pub type Rucksack = Vec<Priority>;
 pub type Rucksacks = Vec<Rucksack>;
 
-const MAX_PRIORITY: Priority = 26 * 2;
+pub const MAX_PRIORITY: Priority = 26 * 2;
 
 pub fn find_common_in_groups(groups: &[Rucksack]) -> Option<Priority> {
     let mut counts = [0; MAX_PRIORITY];
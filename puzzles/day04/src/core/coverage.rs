@@ -0,0 +1,169 @@
+use super::{AssignmentPair, Section, SectionRange, Sections};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Elf {
+    pub pair: usize,
+    pub member: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+    segments: Vec<(SectionRange, Vec<Elf>)>,
+}
+
+impl Coverage {
+    pub fn new(pairs: &[AssignmentPair]) -> Self {
+        let assignments = pairs
+            .iter()
+            .enumerate()
+            .flat_map(|(pair, assignments)| {
+                assignments
+                    .iter()
+                    .enumerate()
+                    .map(move |(member, assignment)| (Elf { pair, member }, assignment))
+            })
+            .filter(|(_, assignment)| !assignment.is_empty())
+            .collect::<Vec<_>>();
+
+        let mut boundaries = assignments
+            .iter()
+            .flat_map(|(_, assignment)| [assignment.start(), assignment.end() + 1])
+            .collect::<Vec<_>>();
+
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        let mut segments = boundaries
+            .windows(2)
+            .map(|window| (window[0]..=window[1] - 1, Vec::new()))
+            .collect::<Vec<_>>();
+
+        for (elf, assignment) in assignments {
+            let first = boundaries.binary_search(&assignment.start()).unwrap();
+            let last = boundaries.binary_search(&(assignment.end() + 1)).unwrap();
+
+            for (_, elves) in segments[first..last].iter_mut() {
+                elves.push(elf);
+            }
+        }
+
+        Self { segments }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&SectionRange, &[Elf])> {
+        self.segments
+            .iter()
+            .map(|(range, elves)| (range, elves.as_slice()))
+    }
+
+    pub fn span(&self) -> Option<SectionRange> {
+        let first = self.segments.first()?;
+        let last = self.segments.last()?;
+        Some(*first.0.start()..=*last.0.end())
+    }
+
+    pub fn elves_at(&self, section: Section) -> &[Elf] {
+        self.segments
+            .binary_search_by(|(range, _)| {
+                if range.end() < &section {
+                    std::cmp::Ordering::Less
+                } else if range.start() > &section {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .map(|i| self.segments[i].1.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn count_at(&self, section: Section) -> usize {
+        self.elves_at(section).len()
+    }
+
+    pub fn covered_by<F>(&self, predicate: F) -> Sections
+    where
+        F: Fn(usize) -> bool,
+    {
+        self.segments
+            .iter()
+            .filter(|(_, elves)| predicate(elves.len()))
+            .map(|(range, _)| range.clone())
+            .collect()
+    }
+
+    pub fn uncovered(&self) -> Sections {
+        self.covered_by(|count| count == 0)
+    }
+
+    pub fn covered_by_more_than(&self, k: usize) -> Sections {
+        self.covered_by(|count| count > k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use crate::core::AssignmentPairs;
+
+    use super::*;
+
+    const INPUT: Input = include_str!("../../input-test");
+
+    fn coverage() -> Coverage {
+        let pairs = INPUT
+            .lines()
+            .map(AssignmentPair::try_from)
+            .collect::<Result<AssignmentPairs, _>>()
+            .unwrap();
+        Coverage::new(&pairs)
+    }
+
+    #[test]
+    fn test_span() {
+        assert_eq!(coverage().span(), Some(2..=9));
+        assert_eq!(Coverage::new(&[]).span(), None);
+    }
+
+    #[test]
+    fn test_elves_at() {
+        let coverage = coverage();
+
+        assert_eq!(coverage.elves_at(1), []);
+        assert_eq!(
+            coverage.elves_at(3),
+            [
+                Elf { pair: 0, member: 0 },
+                Elf { pair: 1, member: 0 },
+                Elf { pair: 3, member: 0 },
+                Elf { pair: 3, member: 1 },
+                Elf { pair: 5, member: 0 },
+            ]
+        );
+        assert_eq!(coverage.count_at(9), 1);
+        assert_eq!(coverage.count_at(10), 0);
+    }
+
+    #[test]
+    fn test_uncovered() {
+        assert!(coverage().uncovered().is_empty());
+
+        let pairs = ["1-2,6-7", "9-9,1-1"]
+            .into_iter()
+            .map(AssignmentPair::try_from)
+            .collect::<Result<AssignmentPairs, _>>()
+            .unwrap();
+
+        assert_eq!(
+            Coverage::new(&pairs).uncovered(),
+            [3..=5, 8..=8].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn test_covered_by_more_than() {
+        assert_eq!(coverage().covered_by_more_than(7), Sections::from(6..=6));
+        assert!(coverage().covered_by_more_than(8).is_empty());
+    }
+}
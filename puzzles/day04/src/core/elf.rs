@@ -1,13 +1,59 @@
 use anyhow::Context;
 
-use super::SectionRange;
+use super::{Section, SectionRange, Sections};
 
 pub type AssignmentPairs = Vec<AssignmentPair>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Assignment(SectionRange);
 
 impl Assignment {
+    pub fn new(start: Section, end: Section) -> Self {
+        Self(start..=end)
+    }
+
+    pub fn range(&self) -> &SectionRange {
+        &self.0
+    }
+
+    pub fn start(&self) -> Section {
+        *self.0.start()
+    }
+
+    pub fn end(&self) -> Section {
+        *self.0.end()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        if self.is_empty() {
+            0
+        } else {
+            self.end() - self.start() + 1
+        }
+    }
+
+    pub fn sections(&self) -> Sections {
+        self.0.clone().into()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let start = self.start().max(other.start());
+        let end = self.end().min(other.end());
+        (start <= end).then(|| Self::new(start, end))
+    }
+
+    pub fn union(&self, other: &Self) -> Sections {
+        self.sections().union(&other.sections())
+    }
+
+    pub fn difference(&self, other: &Self) -> Sections {
+        self.sections().difference(&other.sections())
+    }
+
     pub fn contains(&self, other: &Self) -> bool {
         self.0.start() <= other.0.start() && other.0.end() <= self.0.end()
     }
@@ -34,10 +80,34 @@ impl TryFrom<&str> for Assignment {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AssignmentPair(Assignment, Assignment);
 
 impl AssignmentPair {
+    pub fn new(a: Assignment, b: Assignment) -> Self {
+        Self(a, b)
+    }
+
+    pub fn first(&self) -> &Assignment {
+        &self.0
+    }
+
+    pub fn second(&self) -> &Assignment {
+        &self.1
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Assignment> {
+        [&self.0, &self.1].into_iter()
+    }
+
+    pub fn intersection(&self) -> Option<Assignment> {
+        self.0.intersection(&self.1)
+    }
+
+    pub fn union(&self) -> Sections {
+        self.0.union(&self.1)
+    }
+
     pub fn has_redundancy(&self) -> bool {
         self.0.contains(&self.1) || self.1.contains(&self.0)
     }
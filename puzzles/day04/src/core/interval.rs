@@ -0,0 +1,209 @@
+use super::{Section, SectionRange};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sections(Vec<SectionRange>);
+
+impl Sections {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ranges(&self) -> &[SectionRange] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0
+            .iter()
+            .map(|range| range.end() - range.start() + 1)
+            .sum()
+    }
+
+    pub fn contains(&self, section: Section) -> bool {
+        self.0
+            .binary_search_by(|range| {
+                if range.end() < &section {
+                    std::cmp::Ordering::Less
+                } else if range.start() > &section {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Section> + '_ {
+        self.0.iter().flat_map(|range| range.clone())
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.0.iter().chain(other.0.iter()).cloned().collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.0.len() && j < other.0.len() {
+            let (a, b) = (&self.0[i], &other.0[j]);
+
+            let start = *a.start().max(b.start());
+            let end = *a.end().min(b.end());
+
+            if start <= end {
+                ranges.push(start..=end);
+            }
+
+            if a.end() < b.end() {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self(ranges)
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+
+        for range in self.0.iter() {
+            let mut start = *range.start();
+            let end = *range.end();
+
+            while j < other.0.len() && other.0[j].end() < &start {
+                j += 1;
+            }
+
+            let mut k = j;
+
+            while start <= end && k < other.0.len() && other.0[k].start() <= &end {
+                let cut = &other.0[k];
+
+                if *cut.start() > start {
+                    ranges.push(start..=cut.start() - 1);
+                }
+
+                start = cut.end() + 1;
+                k += 1;
+            }
+
+            if start <= end {
+                ranges.push(start..=end);
+            }
+        }
+
+        Self(ranges)
+    }
+}
+
+impl FromIterator<SectionRange> for Sections {
+    fn from_iter<T: IntoIterator<Item = SectionRange>>(iter: T) -> Self {
+        let mut ranges = iter
+            .into_iter()
+            .filter(|range| !range.is_empty())
+            .collect::<Vec<_>>();
+
+        ranges.sort_by_key(|range| *range.start());
+
+        let mut merged: Vec<SectionRange> = Vec::with_capacity(ranges.len());
+
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if *range.start() <= last.end() + 1 => {
+                    if range.end() > last.end() {
+                        *last = *last.start()..=*range.end();
+                    }
+                }
+                _ => merged.push(range),
+            }
+        }
+
+        Self(merged)
+    }
+}
+
+impl From<SectionRange> for Sections {
+    fn from(range: SectionRange) -> Self {
+        std::iter::once(range).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sections(ranges: &[(Section, Section)]) -> Sections {
+        ranges.iter().map(|&(start, end)| start..=end).collect()
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            sections(&[(5, 7), (1, 2), (3, 4), (6, 9)]),
+            sections(&[(1, 9)])
+        );
+        assert_eq!(sections(&[(1, 2), (4, 5)]).ranges().len(), 2);
+        assert!(sections(&[(3, 2)]).is_empty());
+    }
+
+    #[test]
+    fn test_contains() {
+        let s = sections(&[(1, 2), (5, 7)]);
+        assert!(s.contains(1));
+        assert!(s.contains(6));
+        assert!(!s.contains(3));
+        assert!(!s.contains(8));
+    }
+
+    #[test]
+    fn test_union() {
+        assert_eq!(
+            sections(&[(1, 3)]).union(&sections(&[(5, 6)])),
+            sections(&[(1, 3), (5, 6)])
+        );
+        assert_eq!(
+            sections(&[(1, 3)]).union(&sections(&[(4, 6)])),
+            sections(&[(1, 6)])
+        );
+    }
+
+    #[test]
+    fn test_intersection() {
+        assert_eq!(
+            sections(&[(1, 5), (8, 10)]).intersection(&sections(&[(3, 9)])),
+            sections(&[(3, 5), (8, 9)])
+        );
+        assert!(sections(&[(1, 2)])
+            .intersection(&sections(&[(3, 4)]))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_difference() {
+        assert_eq!(
+            sections(&[(1, 10)]).difference(&sections(&[(2, 3), (5, 5), (9, 12)])),
+            sections(&[(1, 1), (4, 4), (6, 8)])
+        );
+        assert_eq!(
+            sections(&[(1, 3), (5, 8)]).difference(&sections(&[(2, 6)])),
+            sections(&[(1, 1), (7, 8)])
+        );
+        assert!(sections(&[(2, 4)])
+            .difference(&sections(&[(1, 5)]))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_len() {
+        let s = sections(&[(1, 3), (5, 5)]);
+        assert_eq!(s.len(), 4);
+        assert_eq!(s.iter().collect::<Vec<_>>(), [1, 2, 3, 5]);
+    }
+}
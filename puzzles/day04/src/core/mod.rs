@@ -3,3 +3,12 @@ pub type SectionRange = std::ops::RangeInclusive<Section>;
 
 pub mod elf;
 pub use elf::*;
+
+pub mod interval;
+pub use interval::*;
+
+pub mod coverage;
+pub use coverage::*;
+
+pub mod reassign;
+pub use reassign::*;
@@ -0,0 +1,200 @@
+use anyhow::{anyhow, Context};
+
+use super::{Assignment, AssignmentPair, Coverage, Section, SectionRange, Sections};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssignmentDiff {
+    pub removed: Sections,
+    pub added: Sections,
+}
+
+impl AssignmentDiff {
+    pub fn new(before: &Assignment, after: &Assignment) -> Self {
+        Self {
+            removed: before.sections().difference(&after.sections()),
+            added: after.sections().difference(&before.sections()),
+        }
+    }
+
+    pub fn cost(&self) -> usize {
+        self.removed.len() + self.added.len()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairReassignment {
+    pub before: AssignmentPair,
+    pub after: AssignmentPair,
+    pub diffs: [AssignmentDiff; 2],
+}
+
+impl PairReassignment {
+    pub fn cost(&self) -> usize {
+        self.diffs.iter().map(AssignmentDiff::cost).sum()
+    }
+
+    pub fn is_unchanged(&self) -> bool {
+        self.cost() == 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reassignment {
+    pub pairs: Vec<PairReassignment>,
+    pub cost: usize,
+}
+
+fn shrink_into(assignment: &Assignment, bounds: SectionRange, nearest: Section) -> Assignment {
+    let start = assignment.start().max(*bounds.start());
+    let end = assignment.end().min(*bounds.end());
+
+    if assignment.is_empty() || start > end {
+        Assignment::new(nearest, nearest)
+    } else {
+        Assignment::new(start, end)
+    }
+}
+
+fn cost(before: &Assignment, after: &Assignment) -> usize {
+    AssignmentDiff::new(before, after).cost()
+}
+
+pub fn reassign_pair(
+    pair: &AssignmentPair,
+    bounds: SectionRange,
+) -> anyhow::Result<PairReassignment> {
+    let (lo, hi) = (*bounds.start(), *bounds.end());
+
+    if hi <= lo {
+        return Err(anyhow!(
+            "two disjoint assignments will not fit in sections {}-{}",
+            lo,
+            hi
+        ));
+    }
+
+    let (a, b) = (pair.first(), pair.second());
+
+    let mut best: Option<(usize, Assignment, Assignment)> = None;
+
+    for split in lo..hi {
+        let left = lo..=split;
+        let right = split + 1..=hi;
+
+        let candidates = [
+            (
+                shrink_into(a, left.clone(), split),
+                shrink_into(b, right.clone(), split + 1),
+            ),
+            (
+                shrink_into(a, right, split + 1),
+                shrink_into(b, left, split),
+            ),
+        ];
+
+        for (a2, b2) in candidates {
+            let total = cost(a, &a2) + cost(b, &b2);
+            if best.as_ref().is_none_or(|(other, _, _)| total < *other) {
+                best = Some((total, a2, b2));
+            }
+        }
+    }
+
+    let (_, a2, b2) = best.expect("bounds contain at least one split");
+
+    Ok(PairReassignment {
+        diffs: [AssignmentDiff::new(a, &a2), AssignmentDiff::new(b, &b2)],
+        before: pair.clone(),
+        after: AssignmentPair::new(a2, b2),
+    })
+}
+
+pub fn reassign_within(
+    pairs: &[AssignmentPair],
+    bounds: SectionRange,
+) -> anyhow::Result<Reassignment> {
+    let pairs = pairs
+        .iter()
+        .enumerate()
+        .map(|(i, pair)| {
+            reassign_pair(pair, bounds.clone()).with_context(|| format!("pair number {}", i + 1))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()
+        .context("no overlap-free assignment exists")?;
+
+    let cost = pairs.iter().map(PairReassignment::cost).sum();
+
+    Ok(Reassignment { pairs, cost })
+}
+
+pub fn reassign(pairs: &[AssignmentPair]) -> anyhow::Result<Reassignment> {
+    let bounds = Coverage::new(pairs)
+        .span()
+        .context("no sections assigned")?;
+    reassign_within(pairs, bounds)
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use crate::core::AssignmentPairs;
+
+    use super::*;
+
+    const INPUT: Input = include_str!("../../input-test");
+
+    fn parse(lines: &str) -> AssignmentPairs {
+        lines
+            .lines()
+            .map(AssignmentPair::try_from)
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reassign() {
+        let reassignment = reassign(&parse(INPUT)).unwrap();
+
+        for pair in reassignment.pairs.iter() {
+            assert!(!pair.after.has_overlap());
+            assert!(!pair.after.has_redundancy());
+        }
+
+        let costs = reassignment
+            .pairs
+            .iter()
+            .map(PairReassignment::cost)
+            .collect::<Vec<_>>();
+
+        assert_eq!(costs, [0, 0, 1, 6, 1, 3]);
+        assert_eq!(reassignment.cost, 11);
+    }
+
+    #[test]
+    fn test_reassign_diff() {
+        let reassignment = reassign(&parse("5-7,7-9")).unwrap();
+        let pair = &reassignment.pairs[0];
+
+        assert!(!pair.is_unchanged());
+        assert_eq!(pair.after.first().range(), &(5..=6));
+        assert_eq!(pair.after.second().range(), &(7..=9));
+        assert_eq!(pair.diffs[0].removed, Sections::from(7..=7));
+        assert!(pair.diffs[0].added.is_empty());
+    }
+
+    #[test]
+    fn test_reassign_moves_single_section() {
+        let reassignment = reassign_within(&parse("3-3,3-3"), 1..=5).unwrap();
+        let pair = &reassignment.pairs[0];
+
+        assert!(!pair.after.has_overlap());
+        assert_eq!(pair.cost(), 2);
+    }
+
+    #[test]
+    fn test_reassign_impossible() {
+        assert!(reassign(&parse("3-3,3-3")).is_err());
+        assert!(reassign_within(&parse("1-2,2-3"), 4..=4).is_err());
+    }
+}
@@ -1,29 +1,54 @@
-use anyhow::Context;
+use anyhow::{ensure, Context};
 
-use super::Stacks;
+use super::{Crate, Stacks};
 
 pub trait Crane {
+    fn lifts(count: usize) -> usize;
+
     fn move_crates(stacks: &mut Stacks, count: usize, from: usize, to: usize)
         -> anyhow::Result<()>;
 }
 
+// Moves are checked up front, so a move that cannot be completed leaves the stacks untouched.
+fn check_height(stacks: &Stacks, from: usize, count: usize) -> anyhow::Result<()> {
+    let height = stacks.0[from].len();
+    ensure!(
+        count <= height,
+        "stack number {} has {} crates, but {} must be moved",
+        from + 1,
+        height,
+        count
+    );
+    Ok(())
+}
+
+fn pick_up(stacks: &mut Stacks, from: usize, i: usize) -> anyhow::Result<Crate> {
+    stacks.pick_up(from).with_context(|| {
+        format!(
+            "stack number {} is empty on iteration number {}",
+            from + 1,
+            i + 1
+        )
+    })
+}
+
 pub struct Crane9000;
 
 impl Crane for Crane9000 {
+    fn lifts(count: usize) -> usize {
+        count
+    }
+
     fn move_crates(
         stacks: &mut Stacks,
         count: usize,
         from: usize,
         to: usize,
     ) -> anyhow::Result<()> {
+        check_height(stacks, from, count)?;
+
         for i in 0..count {
-            let c = stacks.pick_up(from).with_context(|| {
-                format!(
-                    "stack number {} is empty on iteration number {}",
-                    from + 1,
-                    i + 1
-                )
-            })?;
+            let c = pick_up(stacks, from, i)?;
             stacks.put_down(to, c);
         }
 
@@ -34,26 +59,62 @@ impl Crane for Crane9000 {
 pub struct Crane9001;
 
 impl Crane for Crane9001 {
+    fn lifts(count: usize) -> usize {
+        count.min(1)
+    }
+
     fn move_crates(
         stacks: &mut Stacks,
         count: usize,
         from: usize,
         to: usize,
     ) -> anyhow::Result<()> {
+        check_height(stacks, from, count)?;
+
         let mut buffer: Vec<_> = Default::default();
 
         for i in 0..count {
-            buffer.push(stacks.pick_up(from).with_context(|| {
-                format!(
-                    "stack number {} is empty on iteration number {}",
-                    from + 1,
-                    i + 1
-                )
-            })?);
+            buffer.push(pick_up(stacks, from, i)?);
+        }
+
+        while let Some(c) = buffer.pop() {
+            stacks.put_down(to, c);
         }
 
-        while !buffer.is_empty() {
-            stacks.put_down(to, buffer.pop().unwrap());
+        Ok(())
+    }
+}
+
+pub struct CraneCapacity<const K: usize>;
+
+impl<const K: usize> CraneCapacity<K> {
+    // Using a crane that is unable to lift any crates fails to compile.
+    const CAPACITY: usize = {
+        assert!(K > 0, "crane is unable to lift any crates");
+        K
+    };
+}
+
+impl<const K: usize> Crane for CraneCapacity<K> {
+    fn lifts(count: usize) -> usize {
+        count.div_ceil(Self::CAPACITY)
+    }
+
+    fn move_crates(
+        stacks: &mut Stacks,
+        count: usize,
+        from: usize,
+        to: usize,
+    ) -> anyhow::Result<()> {
+        check_height(stacks, from, count)?;
+
+        let mut moved = 0;
+
+        while moved < count {
+            let batch = (count - moved).min(Self::CAPACITY);
+            Crane9001::move_crates(stacks, batch, from, to)
+                .with_context(|| format!("lift number {}", moved / Self::CAPACITY + 1))?;
+            moved += batch;
         }
 
         Ok(())
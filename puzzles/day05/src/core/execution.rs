@@ -0,0 +1,45 @@
+use std::fmt;
+
+use super::Movement;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Constraints {
+    pub max_height: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CostModel {
+    pub per_lift: usize,
+    pub per_move: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Execution {
+    pub steps: usize,
+    pub lifts: usize,
+    pub cost: usize,
+}
+
+#[derive(Debug)]
+pub struct StepError {
+    pub step: usize,
+    pub movement: Movement,
+    pub source: anyhow::Error,
+}
+
+impl fmt::Display for StepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "step number {} ({}) failed and the procedure was rolled back",
+            self.step + 1,
+            self.movement
+        )
+    }
+}
+
+impl std::error::Error for StepError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
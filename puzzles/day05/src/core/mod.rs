@@ -12,3 +12,9 @@ pub use procedure::*;
 
 pub mod ship;
 pub use ship::*;
+
+pub mod planner;
+pub use planner::*;
+
+pub mod execution;
+pub use execution::*;
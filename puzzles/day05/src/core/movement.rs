@@ -1,8 +1,10 @@
+use std::fmt;
+
 use anyhow::{anyhow, ensure, Context};
 
 use super::{Crane, Stacks};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Movement {
     pub count: usize,
     pub from: usize,
@@ -11,11 +13,28 @@ pub struct Movement {
 
 impl Movement {
     pub fn execute<C: Crane>(&self, stacks: &mut Stacks) -> anyhow::Result<()> {
+        let num_stacks = stacks.0.len();
+
+        for (name, number) in [("source", self.from), ("destination", self.to)] {
+            ensure!(
+                (1..=num_stacks).contains(&number),
+                "{} stack number {} does not exist",
+                name,
+                number
+            );
+        }
+
         C::move_crates(stacks, self.count, self.from - 1, self.to - 1)
             .with_context(|| format!("unable to execute command: {self:?}"))
     }
 }
 
+impl fmt::Display for Movement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
+
 impl TryFrom<&str> for Movement {
     type Error = anyhow::Error;
 
@@ -50,3 +69,41 @@ impl TryFrom<&str> for Movement {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use crate::answer::parse1;
+    use crate::core::{Crane9000, Crane9001, CraneCapacity};
+
+    use super::*;
+
+    const INPUT: Input = include_str!("../../input-test");
+
+    fn assert_untouched<C: Crane>() -> anyhow::Result<()> {
+        let (stacks, _) = parse1(INPUT)?;
+        let mut executed = stacks.clone();
+
+        let error = Movement {
+            count: 4,
+            from: 2,
+            to: 1,
+        }
+        .execute::<C>(&mut executed)
+        .unwrap_err();
+
+        assert!(format!("{:#}", error).contains("stack number 2 has 3 crates, but 4 must be moved"));
+        assert_eq!(executed, stacks);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_execute_failure() -> anyhow::Result<()> {
+        assert_untouched::<Crane9000>()?;
+        assert_untouched::<Crane9001>()?;
+        assert_untouched::<CraneCapacity<2>>()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::{anyhow, ensure};
+
+use super::{Crane, Crate, Message, Movement, Procedure, Stacks};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    Message(Message),
+    Arrangement(Stacks),
+}
+
+impl Target {
+    fn tops(&self) -> Vec<Option<Crate>> {
+        match self {
+            Self::Message(message) => message.chars().map(|c| (c != ' ').then_some(c)).collect(),
+            Self::Arrangement(stacks) => stacks.top().into_iter().map(|c| c.copied()).collect(),
+        }
+    }
+
+    pub fn is_reached(&self, stacks: &Stacks) -> bool {
+        match self {
+            Self::Message(_) => {
+                let tops: Vec<_> = stacks.top().into_iter().map(|c| c.copied()).collect();
+                tops == self.tops()
+            }
+            Self::Arrangement(target) => stacks == target,
+        }
+    }
+
+    // Every movement touches two stacks, so at least half of the stacks that still differ from
+    // the target need to be visited again.
+    fn lower_bound(&self, stacks: &Stacks) -> usize {
+        let differing = match self {
+            Self::Message(_) => stacks
+                .top()
+                .into_iter()
+                .zip(self.tops())
+                .filter(|(a, b)| a.copied() != *b)
+                .count(),
+            Self::Arrangement(target) => stacks
+                .0
+                .iter()
+                .zip(target.0.iter())
+                .filter(|(a, b)| a != b)
+                .count(),
+        };
+
+        differing.div_ceil(2)
+    }
+
+    fn validate(&self, stacks: &Stacks) -> anyhow::Result<()> {
+        let mut crates: Vec<Crate> = stacks.0.iter().flatten().copied().collect();
+        crates.sort_unstable();
+
+        let tops = self.tops();
+
+        ensure!(
+            tops.len() == stacks.0.len(),
+            "target has {} stacks, but there are {}",
+            tops.len(),
+            stacks.0.len()
+        );
+
+        match self {
+            Self::Message(_) => {
+                let mut remaining = crates.clone();
+                for c in tops.iter().flatten() {
+                    let i = remaining
+                        .binary_search(c)
+                        .map_err(|_| anyhow!("there are not enough crates marked {:?}", c))?;
+                    remaining.remove(i);
+                }
+                ensure!(
+                    crates.is_empty() || tops.iter().any(|c| c.is_some()),
+                    "every stack in the target is empty, but there are crates to place"
+                );
+            }
+            Self::Arrangement(target) => {
+                let mut expected: Vec<Crate> = target.0.iter().flatten().copied().collect();
+                expected.sort_unstable();
+                ensure!(
+                    crates == expected,
+                    "target arrangement does not contain the same crates"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn movements(stacks: &Stacks) -> impl Iterator<Item = Movement> + '_ {
+    let n = stacks.0.len();
+
+    (0..n).flat_map(move |from| {
+        (0..n).filter(move |&to| to != from).flat_map(move |to| {
+            (1..=stacks.0[from].len()).map(move |count| Movement {
+                count,
+                from: from + 1,
+                to: to + 1,
+            })
+        })
+    })
+}
+
+pub fn plan<C: Crane>(
+    stacks: &Stacks,
+    target: &Target,
+    max_moves: usize,
+) -> anyhow::Result<Procedure> {
+    target.validate(stacks)?;
+
+    let mut parents: HashMap<Stacks, Option<(Stacks, Movement)>> = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    parents.insert(stacks.clone(), None);
+    queue.push_back((stacks.clone(), 0));
+
+    while let Some((current, depth)) = queue.pop_front() {
+        if target.is_reached(&current) {
+            let mut movements = Vec::new();
+            let mut state = &current;
+
+            while let Some(Some((parent, movement))) = parents.get(state) {
+                movements.push(movement.clone());
+                state = parent;
+            }
+
+            movements.reverse();
+
+            return Ok(Procedure(movements));
+        }
+
+        if depth + target.lower_bound(&current) > max_moves {
+            continue;
+        }
+
+        for movement in movements(&current) {
+            let mut next = current.clone();
+            movement.execute::<C>(&mut next)?;
+
+            if parents.contains_key(&next) {
+                continue;
+            }
+
+            parents.insert(next.clone(), Some((current.clone(), movement)));
+            queue.push_back((next, depth + 1));
+        }
+    }
+
+    Err(anyhow!(
+        "target is not reachable within {} movements",
+        max_moves
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use crate::answer::parse1;
+    use crate::core::{decode_message, Crane9000, Crane9001};
+
+    use super::*;
+
+    const INPUT: Input = include_str!("../../input-test");
+
+    fn assert_plan<C: Crane>(target: Target, max_len: usize) -> anyhow::Result<()> {
+        let (stacks, _) = parse1(INPUT)?;
+
+        let procedure = plan::<C>(&stacks, &target, max_len)?;
+        assert!(procedure.0.len() <= max_len);
+
+        let mut result = stacks.clone();
+        procedure.execute::<C>(&mut result)?;
+        assert!(target.is_reached(&result));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_message() -> anyhow::Result<()> {
+        let (stacks, procedure) = parse1(INPUT)?;
+
+        for message in [
+            decode_message::<Crane9000>(&stacks, &procedure)?,
+            decode_message::<Crane9001>(&stacks, &procedure)?,
+        ] {
+            assert_plan::<Crane9000>(Target::Message(message.clone()), 4)?;
+            assert_plan::<Crane9001>(Target::Message(message), 4)?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_arrangement() -> anyhow::Result<()> {
+        let (stacks, procedure) = parse1(INPUT)?;
+
+        let mut target = stacks.clone();
+        procedure.execute::<Crane9001>(&mut target)?;
+
+        assert_plan::<Crane9001>(Target::Arrangement(target), 4)
+    }
+
+    #[test]
+    fn test_plan_is_shortest() -> anyhow::Result<()> {
+        let stacks = Stacks(vec![vec!['A', 'B', 'C'], vec![]]);
+
+        assert_eq!(
+            plan::<Crane9000>(&stacks, &Target::Message("C ".into()), 4)?,
+            Procedure(vec![])
+        );
+
+        let target = Target::Arrangement(Stacks(vec![vec!['A'], vec!['C', 'B']]));
+        assert_eq!(plan::<Crane9000>(&stacks, &target, 4)?.0.len(), 1);
+        assert_eq!(plan::<Crane9001>(&stacks, &target, 4)?.0.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_unreachable() -> anyhow::Result<()> {
+        let (stacks, _) = parse1(INPUT)?;
+
+        assert!(plan::<Crane9000>(&stacks, &Target::Message("XYZ".into()), 4).is_err());
+        assert!(plan::<Crane9000>(&stacks, &Target::Message("   ".into()), 4).is_err());
+        assert!(plan::<Crane9000>(&stacks, &Target::Message("CM".into()), 4).is_err());
+        assert!(plan::<Crane9000>(&stacks, &Target::Message("DDD".into()), 4).is_err());
+
+        Ok(())
+    }
+}
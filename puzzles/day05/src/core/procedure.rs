@@ -1,6 +1,11 @@
-use super::{Crane, Movement, Stacks};
+use std::fmt;
+use std::marker::PhantomData;
 
-#[derive(Debug, Clone)]
+use anyhow::ensure;
+
+use super::{Constraints, CostModel, Crane, Execution, Movement, Stacks, StepError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Procedure(pub Vec<Movement>);
 
 impl Procedure {
@@ -10,4 +15,273 @@ impl Procedure {
         }
         Ok(())
     }
+
+    pub fn execute_transaction<C: Crane>(
+        &self,
+        stacks: &mut Stacks,
+        constraints: &Constraints,
+        costs: &CostModel,
+    ) -> Result<Execution, StepError> {
+        let snapshot = stacks.clone();
+        let mut execution = Execution::default();
+
+        for (step, movement) in self.0.iter().enumerate() {
+            let result = movement.execute::<C>(stacks).and_then(|_| {
+                if let Some(max_height) = constraints.max_height {
+                    let height = stacks.0[movement.to - 1].len();
+                    ensure!(
+                        height <= max_height,
+                        "stack number {} is {} crates high, but the limit is {}",
+                        movement.to,
+                        height,
+                        max_height
+                    );
+                }
+                Ok(())
+            });
+
+            if let Err(source) = result {
+                *stacks = snapshot;
+                return Err(StepError {
+                    step,
+                    movement: movement.clone(),
+                    source,
+                });
+            }
+
+            let lifts = C::lifts(movement.count);
+
+            execution.steps += 1;
+            execution.lifts += lifts;
+            execution.cost += costs.per_move + lifts * costs.per_lift;
+        }
+
+        Ok(execution)
+    }
+
+    pub fn replay<C: Crane>(&self, stacks: &Stacks) -> Replay<'_, C> {
+        Replay {
+            stacks: Some(stacks.clone()),
+            movements: self.0.iter(),
+            crane: PhantomData,
+        }
+    }
+}
+
+impl fmt::Display for Procedure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, movement) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", movement)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Replay<'a, C: Crane> {
+    stacks: Option<Stacks>,
+    movements: std::slice::Iter<'a, Movement>,
+    crane: PhantomData<C>,
+}
+
+impl<C: Crane> Iterator for Replay<'_, C> {
+    type Item = anyhow::Result<Stacks>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stacks = self.stacks.as_mut()?;
+        let movement = self.movements.next()?;
+
+        match movement.execute::<C>(stacks) {
+            Ok(()) => Some(Ok(stacks.clone())),
+            Err(error) => {
+                self.stacks = None;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use crate::answer::parse1;
+    use crate::core::{Crane9000, Crane9001, CraneCapacity};
+
+    use super::*;
+
+    const INPUT: Input = include_str!("../../input-test");
+
+    fn assert_replay<C: Crane>(expected: &[&str]) -> anyhow::Result<()> {
+        let (stacks, procedure) = parse1(INPUT)?;
+
+        let states = procedure
+            .replay::<C>(&stacks)
+            .map(|stacks| stacks.map(|stacks| stacks.to_string()))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        assert_eq!(states, expected);
+
+        let mut executed = stacks.clone();
+        procedure.execute::<C>(&mut executed)?;
+        assert_eq!(states.last().unwrap(), &executed.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_9000() -> anyhow::Result<()> {
+        assert_replay::<Crane9000>(&[
+            "[D]        \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ",
+            "        [Z]\n        [N]\n    [C] [D]\n    [M] [P]\n 1   2   3 ",
+            "        [Z]\n        [N]\n[M]     [D]\n[C]     [P]\n 1   2   3 ",
+            "        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3 ",
+        ])
+    }
+
+    #[test]
+    fn test_replay_9001() -> anyhow::Result<()> {
+        assert_replay::<Crane9001>(&[
+            "[D]        \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ",
+            "        [D]\n        [N]\n    [C] [Z]\n    [M] [P]\n 1   2   3 ",
+            "        [D]\n        [N]\n[C]     [Z]\n[M]     [P]\n 1   2   3 ",
+            "        [D]\n        [N]\n        [Z]\n[M] [C] [P]\n 1   2   3 ",
+        ])
+    }
+
+    #[test]
+    fn test_replay_stops_on_error() {
+        let stacks = Stacks(vec![vec!['A'], vec![]]);
+        let procedure = Procedure(vec![
+            Movement {
+                count: 2,
+                from: 1,
+                to: 2,
+            },
+            Movement {
+                count: 1,
+                from: 1,
+                to: 2,
+            },
+        ]);
+
+        let mut replay = procedure.replay::<Crane9000>(&stacks);
+        assert!(replay.next().unwrap().is_err());
+        assert!(replay.next().is_none());
+    }
+
+    const COSTS: CostModel = CostModel {
+        per_lift: 3,
+        per_move: 10,
+    };
+
+    #[test]
+    fn test_execute_transaction() -> anyhow::Result<()> {
+        let (stacks, procedure) = parse1(INPUT)?;
+
+        let mut result = stacks.clone();
+        let execution = procedure.execute_transaction::<Crane9000>(
+            &mut result,
+            &Constraints::default(),
+            &COSTS,
+        )?;
+
+        assert_eq!(
+            execution,
+            Execution {
+                steps: 4,
+                lifts: 7,
+                cost: 61,
+            }
+        );
+
+        let mut expected = stacks.clone();
+        procedure.execute::<Crane9000>(&mut expected)?;
+        assert_eq!(result, expected);
+
+        let mut result = stacks.clone();
+        let execution = procedure.execute_transaction::<Crane9001>(
+            &mut result,
+            &Constraints::default(),
+            &COSTS,
+        )?;
+
+        assert_eq!(execution.lifts, 4);
+        assert_eq!(execution.cost, 52);
+
+        let mut result = stacks.clone();
+        let execution = procedure.execute_transaction::<CraneCapacity<2>>(
+            &mut result,
+            &Constraints::default(),
+            &COSTS,
+        )?;
+
+        assert_eq!(execution.lifts, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_execute_transaction_rolls_back() -> anyhow::Result<()> {
+        let (stacks, mut procedure) = parse1(INPUT)?;
+
+        procedure.0.insert(
+            2,
+            Movement {
+                count: 5,
+                from: 1,
+                to: 3,
+            },
+        );
+
+        let mut result = stacks.clone();
+        let error = procedure
+            .execute_transaction::<Crane9000>(&mut result, &Constraints::default(), &COSTS)
+            .unwrap_err();
+
+        assert_eq!(error.step, 2);
+        assert_eq!(result, stacks);
+
+        let (stacks, procedure) = parse1(INPUT)?;
+
+        let constraints = Constraints {
+            max_height: Some(3),
+        };
+
+        let mut result = stacks.clone();
+        let error = procedure
+            .execute_transaction::<Crane9001>(&mut result, &constraints, &COSTS)
+            .unwrap_err();
+
+        assert_eq!(error.step, 1);
+        assert_eq!(result, stacks);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_crane_capacity() -> anyhow::Result<()> {
+        let (stacks, procedure) = parse1(INPUT)?;
+
+        let mut a = stacks.clone();
+        let mut b = stacks.clone();
+        procedure.execute::<CraneCapacity<1>>(&mut a)?;
+        procedure.execute::<Crane9000>(&mut b)?;
+        assert_eq!(a, b);
+
+        let mut a = stacks.clone();
+        let mut b = stacks.clone();
+        procedure.execute::<CraneCapacity<3>>(&mut a)?;
+        procedure.execute::<Crane9001>(&mut b)?;
+        assert_eq!(a, b);
+
+        let mut stacks = Stacks(vec![vec!['A', 'B', 'C', 'D', 'E'], vec![]]);
+        CraneCapacity::<2>::move_crates(&mut stacks, 5, 0, 1)?;
+        assert_eq!(stacks.0[1], ['D', 'E', 'B', 'C', 'A']);
+
+        Ok(())
+    }
 }
@@ -1,4 +1,5 @@
 use std::convert::TryFrom;
+use std::fmt;
 
 use anyhow::{anyhow, ensure, Context};
 
@@ -7,7 +8,7 @@ const CRATE_WIDTH: usize = 3;
 pub type Crate = char;
 pub type Stack = Vec<Crate>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Stacks(pub Vec<Stack>);
 
 impl Stacks {
@@ -24,6 +25,33 @@ impl Stacks {
     }
 }
 
+impl fmt::Display for Stacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let height = self.0.iter().map(|stack| stack.len()).max().unwrap_or(0);
+
+        for level in (0..height).rev() {
+            let row = self
+                .0
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(c) => format!("[{}]", c),
+                    None => " ".repeat(CRATE_WIDTH),
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            writeln!(f, "{}", row)?;
+        }
+
+        let header = (1..=self.0.len())
+            .map(|i| format!("{:^width$}", i, width = CRATE_WIDTH))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        write!(f, "{}", header)
+    }
+}
+
 impl TryFrom<&str> for Stacks {
     type Error = anyhow::Error;
 
@@ -89,3 +117,36 @@ impl TryFrom<&str> for Stacks {
         Ok(Self(stacks))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use super::*;
+
+    fn assert_round_trip(input: Input) {
+        let drawing = input.split("\n\n").next().unwrap();
+        let stacks = Stacks::try_from(drawing).unwrap();
+        assert_eq!(stacks.to_string(), drawing);
+        assert_eq!(
+            Stacks::try_from(stacks.to_string().as_str()).unwrap(),
+            stacks
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_round_trip(include_str!("../../input-test"));
+        assert_round_trip(include_str!("../../input"));
+    }
+
+    #[test]
+    fn test_display_empty() {
+        let stacks = Stacks(vec![vec![], vec!['A']]);
+        assert_eq!(stacks.to_string(), "    [A]\n 1   2 ");
+        assert_eq!(
+            Stacks::try_from(stacks.to_string().as_str()).unwrap(),
+            stacks
+        );
+    }
+}
@@ -7,7 +7,7 @@ pub type Parsed1 = Parsed;
 pub type Parsed2 = Parsed;
 
 fn parse(input: Input) -> anyhow::Result<DataStream> {
-    Ok(input.chars().collect())
+    Ok(input.trim_end().bytes().collect())
 }
 
 pub fn parse1(input: Input) -> anyhow::Result<Parsed1> {
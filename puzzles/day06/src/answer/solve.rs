@@ -1,6 +1,6 @@
 use anyhow::Context;
 
-use crate::core::find_unique_window;
+use crate::core::find_marker;
 
 use super::{Parsed1, Parsed2};
 
@@ -9,11 +9,11 @@ pub type Solution1 = Solution;
 pub type Solution2 = Solution;
 
 pub fn solve1(data: &Parsed1) -> anyhow::Result<Solution1> {
-    find_unique_window(data.iter(), 4).context("no start-of-packet marker detected")
+    find_marker(data.iter().copied(), 4).context("no start-of-packet marker detected")
 }
 
 pub fn solve2(data: &Parsed2) -> anyhow::Result<Solution2> {
-    find_unique_window(data.iter(), 14).context("no start-of-message marker detected")
+    find_marker(data.iter().copied(), 14).context("no start-of-message marker detected")
 }
 
 #[cfg(test)]
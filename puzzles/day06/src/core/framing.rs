@@ -0,0 +1,236 @@
+use std::fmt;
+use std::ops::Range;
+
+use super::{Marker, MarkerDetector};
+
+pub const START_OF_PACKET: usize = 4;
+pub const START_OF_MESSAGE: usize = 14;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FramingConfig {
+    pub sync_size: usize,
+    pub frame_size: usize,
+}
+
+impl Default for FramingConfig {
+    fn default() -> Self {
+        Self {
+            sync_size: START_OF_PACKET,
+            frame_size: START_OF_MESSAGE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub marker: Marker,
+    pub payload: Vec<u8>,
+}
+
+impl Frame {
+    pub fn payload_range(&self) -> Range<usize> {
+        self.marker.end..self.marker.end + self.payload.len()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FramingError {
+    NeverSynced { length: usize },
+}
+
+impl fmt::Display for FramingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NeverSynced { length } => write!(
+                f,
+                "no start-of-packet marker found in {} bytes of datastream",
+                length
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FramingError {}
+
+#[derive(Debug, Clone)]
+enum State {
+    Syncing,
+    Synced {
+        current: Option<Marker>,
+        pending: Vec<u8>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct FrameDecoder {
+    config: FramingConfig,
+    detector: MarkerDetector,
+    base: usize,
+    position: usize,
+    sync: Option<Marker>,
+    state: State,
+}
+
+impl FrameDecoder {
+    pub fn new(config: FramingConfig) -> Self {
+        Self {
+            config,
+            detector: MarkerDetector::new(&[config.sync_size]),
+            base: 0,
+            position: 0,
+            sync: None,
+            state: State::Syncing,
+        }
+    }
+
+    pub fn sync(&self) -> Option<Marker> {
+        self.sync
+    }
+
+    fn restart(&mut self) {
+        self.detector = MarkerDetector::new(&[self.config.frame_size]);
+        self.base = self.position;
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<Frame> {
+        let mut frames = Vec::new();
+
+        for &byte in chunk {
+            let base = self.base;
+            let found = self.detector.push(byte).next().map(|marker| Marker {
+                size: marker.size,
+                end: base + marker.end,
+            });
+
+            self.position += 1;
+
+            if let State::Synced { pending, .. } = &mut self.state {
+                pending.push(byte);
+            }
+
+            let Some(marker) = found else {
+                continue;
+            };
+
+            match &mut self.state {
+                State::Syncing => {
+                    self.sync = Some(marker);
+                    self.state = State::Synced {
+                        current: None,
+                        pending: Vec::new(),
+                    };
+                }
+                State::Synced { current, pending } => {
+                    pending.truncate(pending.len() - marker.size);
+
+                    if let Some(previous) = current.replace(marker) {
+                        frames.push(Frame {
+                            marker: previous,
+                            payload: std::mem::take(pending),
+                        });
+                    }
+
+                    pending.clear();
+                }
+            }
+
+            self.restart();
+        }
+
+        frames
+    }
+
+    pub fn finish(self) -> Result<Vec<Frame>, FramingError> {
+        match self.state {
+            State::Syncing => Err(FramingError::NeverSynced {
+                length: self.position,
+            }),
+            State::Synced { current, pending } => Ok(current
+                .map(|marker| Frame {
+                    marker,
+                    payload: pending,
+                })
+                .into_iter()
+                .collect()),
+        }
+    }
+}
+
+pub fn decode_frames(data: &[u8], config: FramingConfig) -> Result<Vec<Frame>, FramingError> {
+    let mut decoder = FrameDecoder::new(config);
+    let mut frames = decoder.push(data);
+    frames.extend(decoder.finish()?);
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: FramingConfig = FramingConfig {
+        sync_size: 2,
+        frame_size: 3,
+    };
+
+    #[test]
+    fn test_decode_frames() -> Result<(), FramingError> {
+        let data = concat!("aab", "cde", "eee", "efg", "ggggg", "ghi").as_bytes();
+        let frames = decode_frames(data, CONFIG)?;
+
+        assert_eq!(
+            frames,
+            [
+                Frame {
+                    marker: Marker { size: 3, end: 6 },
+                    payload: b"eee".to_vec(),
+                },
+                Frame {
+                    marker: Marker { size: 3, end: 12 },
+                    payload: b"ggggg".to_vec(),
+                },
+                Frame {
+                    marker: Marker { size: 3, end: 20 },
+                    payload: vec![],
+                },
+            ]
+        );
+
+        for frame in frames.iter() {
+            assert_eq!(&data[frame.payload_range()], frame.payload);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_frames_in_chunks() -> Result<(), FramingError> {
+        let data = include_str!("../../input").trim_end().as_bytes();
+        let expected = decode_frames(data, FramingConfig::default())?;
+
+        for chunk_size in [1, 2, 3, 7, 64, 1000] {
+            let mut decoder = FrameDecoder::new(FramingConfig::default());
+
+            let mut frames = data
+                .chunks(chunk_size)
+                .flat_map(|chunk| decoder.push(chunk))
+                .collect::<Vec<_>>();
+
+            assert_eq!(decoder.sync().map(|marker| marker.end), Some(1109));
+
+            frames.extend(decoder.finish()?);
+
+            assert_eq!(frames, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_never_synced() {
+        assert_eq!(
+            decode_frames(b"aaaaa", CONFIG),
+            Err(FramingError::NeverSynced { length: 5 })
+        );
+        assert_eq!(decode_frames(b"ab", CONFIG), Ok(vec![]));
+    }
+}
@@ -0,0 +1,240 @@
+use std::io::{self, Read};
+
+const ALPHABET_SIZE: usize = 1 << u8::BITS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Marker {
+    pub size: usize,
+    pub end: usize,
+}
+
+impl Marker {
+    pub fn start(&self) -> usize {
+        self.end - self.size
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Window {
+    size: usize,
+    counts: [u32; ALPHABET_SIZE],
+    duplicates: usize,
+}
+
+impl Window {
+    fn new(size: usize) -> Self {
+        Self {
+            size,
+            counts: [0; ALPHABET_SIZE],
+            duplicates: 0,
+        }
+    }
+
+    fn add(&mut self, byte: u8) {
+        let count = &mut self.counts[byte as usize];
+        *count += 1;
+        if *count == 2 {
+            self.duplicates += 1;
+        }
+    }
+
+    fn remove(&mut self, byte: u8) {
+        let count = &mut self.counts[byte as usize];
+        *count -= 1;
+        if *count == 1 {
+            self.duplicates -= 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MarkerDetector {
+    windows: Vec<Window>,
+    history: Vec<u8>,
+    position: usize,
+}
+
+impl MarkerDetector {
+    pub fn new(sizes: &[usize]) -> Self {
+        let capacity = sizes.iter().copied().max().unwrap_or(0);
+
+        Self {
+            windows: sizes.iter().map(|&size| Window::new(size)).collect(),
+            history: vec![0; capacity],
+            position: 0,
+        }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    fn advance(&mut self, byte: u8) {
+        let capacity = self.history.len();
+        let position = self.position;
+
+        for window in self.windows.iter_mut() {
+            if window.size == 0 {
+                continue;
+            }
+            if position >= window.size {
+                window.remove(self.history[(position - window.size) % capacity]);
+            }
+            window.add(byte);
+        }
+
+        if capacity > 0 {
+            self.history[position % capacity] = byte;
+        }
+
+        self.position += 1;
+    }
+
+    // The marker, if any, that window number i completes at the current position.
+    fn marker(&self, i: usize) -> Option<Marker> {
+        let window = &self.windows[i];
+        let end = self.position;
+
+        (window.size > 0 && end >= window.size && window.duplicates == 0).then_some(Marker {
+            size: window.size,
+            end,
+        })
+    }
+
+    pub fn push(&mut self, byte: u8) -> impl Iterator<Item = Marker> + '_ {
+        self.advance(byte);
+        (0..self.windows.len()).filter_map(|i| self.marker(i))
+    }
+}
+
+pub struct Markers<I> {
+    bytes: I,
+    detector: MarkerDetector,
+    window: usize,
+}
+
+impl<I: Iterator<Item = u8>> Iterator for Markers<I> {
+    type Item = Marker;
+
+    fn next(&mut self) -> Option<Marker> {
+        loop {
+            while self.window < self.detector.windows.len() {
+                self.window += 1;
+                if let Some(marker) = self.detector.marker(self.window - 1) {
+                    return Some(marker);
+                }
+            }
+
+            self.detector.advance(self.bytes.next()?);
+            self.window = 0;
+        }
+    }
+}
+
+pub fn find_markers<I>(bytes: I, sizes: &[usize]) -> Markers<I::IntoIter>
+where
+    I: IntoIterator<Item = u8>,
+{
+    let detector = MarkerDetector::new(sizes);
+
+    Markers {
+        bytes: bytes.into_iter(),
+        window: detector.windows.len(),
+        detector,
+    }
+}
+
+pub fn find_marker<I>(bytes: I, size: usize) -> Option<usize>
+where
+    I: IntoIterator<Item = u8>,
+{
+    find_markers(bytes, &[size]).next().map(|marker| marker.end)
+}
+
+pub fn read_markers<R: Read>(mut reader: R, sizes: &[usize]) -> io::Result<Vec<Marker>> {
+    let mut detector = MarkerDetector::new(sizes);
+    let mut markers = Vec::new();
+    let mut buffer = [0; 8192];
+
+    loop {
+        let n = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        for &byte in buffer[..n].iter() {
+            markers.extend(detector.push(byte));
+        }
+    }
+
+    Ok(markers)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn naive_markers(bytes: &[u8], size: usize) -> Vec<usize> {
+        bytes
+            .windows(size)
+            .enumerate()
+            .filter(|(_, window)| window.iter().collect::<HashSet<_>>().len() == size)
+            .map(|(i, _)| i + size)
+            .collect()
+    }
+
+    #[test]
+    fn test_find_markers_matches_naive() {
+        let input = include_str!("../../input").trim_end().as_bytes();
+
+        let markers = find_markers(input.iter().copied(), &[4, 14]).collect::<Vec<_>>();
+
+        for size in [4, 14] {
+            let ends = markers
+                .iter()
+                .filter(|marker| marker.size == size)
+                .map(|marker| marker.end)
+                .collect::<Vec<_>>();
+            assert_eq!(ends, naive_markers(input, size));
+        }
+    }
+
+    #[test]
+    fn test_find_markers_single_pass_order() {
+        let markers = find_markers(*b"abcd", &[2, 3]).collect::<Vec<_>>();
+        assert_eq!(
+            markers,
+            [
+                Marker { size: 2, end: 2 },
+                Marker { size: 2, end: 3 },
+                Marker { size: 3, end: 3 },
+                Marker { size: 2, end: 4 },
+                Marker { size: 3, end: 4 },
+            ]
+        );
+        assert_eq!(markers[2].start(), 0);
+    }
+
+    #[test]
+    fn test_find_marker() {
+        assert_eq!(find_marker(*b"aaaa", 2), None);
+        assert_eq!(find_marker(*b"aabb", 2), Some(3));
+        assert_eq!(find_marker(*b"", 4), None);
+    }
+
+    #[test]
+    fn test_read_markers() -> io::Result<()> {
+        let input = include_str!("../../input");
+
+        let markers = read_markers(input.as_bytes(), &[4, 14])?;
+        let expected = find_markers(input.bytes(), &[4, 14]).collect::<Vec<_>>();
+
+        assert_eq!(markers, expected);
+
+        Ok(())
+    }
+}
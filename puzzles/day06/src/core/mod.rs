@@ -1,4 +1,7 @@
-pub type DataStream = Vec<char>;
+pub type DataStream = Vec<u8>;
 
-pub mod iter;
-pub use iter::*;
+pub mod marker;
+pub use marker::*;
+
+pub mod framing;
+pub use framing::*;
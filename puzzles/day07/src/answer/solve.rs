@@ -1,5 +1,7 @@
 use anyhow::{anyhow, Context};
 
+use crate::core::{space_to_free, DISK_CAPACITY, UPDATE_SIZE};
+
 use super::{Parsed1, Parsed2};
 
 type Solution = usize;
@@ -11,7 +13,8 @@ pub fn solve1(output: &Parsed1) -> anyhow::Result<Solution1> {
 
     let sizes: Vec<_> = file_system
         .directory_sizes()
-        .filter(|&&size| size <= 100_000)
+        .map(|directory| directory.size)
+        .filter(|&size| size <= 100_000)
         .collect();
 
     if !sizes.is_empty() {
@@ -24,22 +27,15 @@ pub fn solve1(output: &Parsed1) -> anyhow::Result<Solution1> {
 pub fn solve2(output: &Parsed2) -> anyhow::Result<Solution2> {
     let file_system = output.reconstruct_file_system()?;
 
-    let capacity: usize = 70_000_000;
-    let available = capacity - file_system.size();
-
-    let required: usize = 30_000_000;
-    let desired = required - available;
+    let desired = space_to_free(&file_system, DISK_CAPACITY, UPDATE_SIZE);
 
     let sizes: Vec<_> = file_system
         .directory_sizes()
-        .filter(|&&size| size >= desired)
+        .map(|directory| directory.size)
+        .filter(|&size| size >= desired)
         .collect();
 
-    sizes
-        .into_iter()
-        .min()
-        .cloned()
-        .context("no directories found")
+    sizes.into_iter().min().context("no directories found")
 }
 
 #[cfg(test)]
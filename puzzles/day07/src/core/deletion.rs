@@ -0,0 +1,455 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context};
+
+use super::{Entry, FileSystem};
+
+pub const DISK_CAPACITY: usize = 70_000_000;
+pub const UPDATE_SIZE: usize = 30_000_000;
+
+pub fn space_to_free(file_system: &FileSystem, capacity: usize, required: usize) -> usize {
+    let available = capacity.saturating_sub(file_system.size());
+    required.saturating_sub(available)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeletionConstraints {
+    pub protected: Vec<String>,
+    pub files: bool,
+    pub directories: bool,
+}
+
+impl Default for DeletionConstraints {
+    fn default() -> Self {
+        Self {
+            protected: Vec::new(),
+            files: true,
+            directories: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeletionPlan {
+    pub paths: Vec<String>,
+    pub freed: usize,
+}
+
+#[derive(Debug, Clone)]
+struct BitSet(Vec<u64>);
+
+impl BitSet {
+    fn new(len: usize) -> Self {
+        Self(vec![0; len / 64 + 1])
+    }
+
+    fn len(&self) -> usize {
+        self.0.len() * 64
+    }
+
+    fn contains(&self, i: usize) -> bool {
+        i < self.len() && self.0[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    fn first_from(&self, start: usize) -> Option<usize> {
+        let mut word = start / 64;
+        let mut bits = self.0.get(word)? & (u64::MAX << (start % 64));
+
+        while bits == 0 {
+            word += 1;
+            bits = *self.0.get(word)?;
+        }
+
+        Some(word * 64 + bits.trailing_zeros() as usize)
+    }
+
+    fn insert(&mut self, i: usize) {
+        self.0[i / 64] |= 1 << (i % 64);
+    }
+
+    // Words are updated from the top down, so the lower words are read before they change.
+    fn union_shifted_self(&mut self, shift: usize) {
+        let (words, bits) = (shift / 64, shift % 64);
+
+        for i in (words..self.0.len()).rev() {
+            let j = i - words;
+            let mut value = self.0[j] << bits;
+            if bits > 0 && j > 0 {
+                value |= self.0[j - 1] >> (64 - bits);
+            }
+            self.0[i] |= value;
+        }
+    }
+
+    fn union_shifted(&mut self, other: &Self, shift: usize) {
+        let (words, bits) = (shift / 64, shift % 64);
+
+        for i in (words..self.0.len()).rev() {
+            let j = i - words;
+            let mut value = other.0[j] << bits;
+            if bits > 0 && j > 0 {
+                value |= other.0[j - 1] >> (64 - bits);
+            }
+            self.0[i] |= value;
+        }
+    }
+}
+
+// Computes, for each entry i in pre-order, every total that can be freed by choosing among the
+// entries from i onward, where choosing an entry skips over its descendants. The sets are swept
+// from the last entry back to the first, and a set is dropped as soon as no earlier entry needs
+// it, so only the sets for the ends of the open directories are held at once.
+struct Sweep<'e> {
+    entries: &'e [Entry],
+    selectable: &'e [bool],
+    ends: &'e [usize],
+    limit: usize,
+    first_use: Vec<usize>,
+}
+
+impl<'e> Sweep<'e> {
+    fn new(entries: &'e [Entry], selectable: &'e [bool], ends: &'e [usize], limit: usize) -> Self {
+        let mut first_use: Vec<usize> = (0..=entries.len()).map(|i| i.saturating_sub(1)).collect();
+
+        for (i, &end) in ends.iter().enumerate() {
+            first_use[end] = first_use[end].min(i);
+        }
+
+        Self {
+            entries,
+            selectable,
+            ends,
+            limit,
+            first_use,
+        }
+    }
+
+    fn run(
+        &self,
+        live: &mut HashMap<usize, BitSet>,
+        from: usize,
+        to: usize,
+        mut visit: impl FnMut(usize, &HashMap<usize, BitSet>),
+    ) {
+        for i in (to..from).rev() {
+            let mut sums = if self.first_use[i + 1] == i {
+                live.remove(&(i + 1)).unwrap()
+            } else {
+                live[&(i + 1)].clone()
+            };
+            let size = self.entries[i].size;
+
+            if self.selectable[i] && size <= self.limit {
+                match live.get(&self.ends[i]) {
+                    Some(other) => sums.union_shifted(other, size),
+                    None => sums.union_shifted_self(size),
+                }
+            }
+
+            live.insert(i, sums);
+            visit(i, live);
+            live.retain(|&j, _| self.first_use[j] < i);
+        }
+    }
+}
+
+fn is_ancestor(ancestor: &str, path: &str) -> bool {
+    ancestor == "/" || path.starts_with(&format!("{ancestor}/"))
+}
+
+pub fn plan_deletion(
+    file_system: &FileSystem,
+    free: usize,
+    constraints: &DeletionConstraints,
+) -> anyhow::Result<DeletionPlan> {
+    if free == 0 {
+        return Ok(DeletionPlan::default());
+    }
+
+    let protected = constraints
+        .protected
+        .iter()
+        .map(|path| {
+            file_system
+                .lookup(path)
+                .map(|entry| entry.path)
+                .with_context(|| format!("invalid protected path: {path:?}"))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let entries: Vec<Entry> = file_system.walk().into_iter().skip(1).collect();
+
+    let selectable: Vec<bool> = entries
+        .iter()
+        .map(|entry| {
+            (if entry.is_file() {
+                constraints.files
+            } else {
+                constraints.directories
+            }) && protected.iter().all(|path| {
+                path != &entry.path
+                    && !is_ancestor(path, &entry.path)
+                    && !is_ancestor(&entry.path, path)
+            })
+        })
+        .collect();
+
+    let n = entries.len();
+
+    let mut ends = vec![n; n];
+    let mut open: Vec<usize> = Vec::new();
+
+    for (i, entry) in entries.iter().enumerate() {
+        while let Some(&j) = open.last() {
+            if is_ancestor(&entries[j].path, &entry.path) {
+                break;
+            }
+            ends[j] = i;
+            open.pop();
+        }
+        open.push(i);
+    }
+
+    // Deleting a directory frees at least as much as any selection inside of it, so the most that
+    // can be freed is the sum over the outermost selectable entries.
+    let mut most = 0;
+    let mut i = 0;
+    while i < n {
+        if selectable[i] {
+            most += entries[i].size;
+            i = ends[i];
+        } else {
+            i += 1;
+        }
+    }
+
+    if most < free {
+        bail!(
+            "at most {} bytes can be freed, but {} are required",
+            most,
+            free
+        );
+    }
+
+    let limit = entries
+        .iter()
+        .zip(selectable.iter())
+        .filter(|(entry, &selectable)| selectable && entry.size >= free)
+        .map(|(entry, _)| entry.size)
+        .min()
+        .unwrap_or(most)
+        .min(most);
+
+    let sweep = Sweep::new(&entries, &selectable, &ends, limit);
+
+    // Holding every set at once would take memory proportional to the number of entries times the
+    // limit, so the live sets are saved every so often and recomputed while reconstructing.
+    let interval = ((n as f64).sqrt().ceil() as usize).max(1);
+
+    let mut live = HashMap::from([(n, BitSet::new(limit))]);
+    live.get_mut(&n).unwrap().insert(0);
+
+    let mut checkpoints = HashMap::from([(n, live.clone())]);
+
+    sweep.run(&mut live, n, 0, |i, live| {
+        if i % interval == 0 {
+            checkpoints.insert(i, live.clone());
+        }
+    });
+
+    let freed = checkpoints[&0][&0]
+        .first_from(free)
+        .filter(|&total| total <= limit)
+        .context("no selection frees enough space")?;
+
+    let mut paths = Vec::new();
+    let mut remaining = freed;
+    let mut i = 0;
+    let mut segment: Option<(usize, HashMap<usize, BitSet>)> = None;
+
+    while remaining > 0 {
+        let start = i - i % interval;
+
+        let sums = match segment {
+            Some((current, ref sums)) if current == start => sums,
+            _ => {
+                let end = (start + interval).min(n);
+                let mut live = checkpoints[&end].clone();
+                let mut sums = live.clone();
+
+                sweep.run(&mut live, end, start, |i, live| {
+                    sums.insert(i, live[&i].clone());
+                });
+
+                &segment.insert((start, sums)).1
+            }
+        };
+
+        let size = entries[i].size;
+
+        if selectable[i] && size <= remaining && sums[&ends[i]].contains(remaining - size) {
+            paths.push(entries[i].path.clone());
+            remaining -= size;
+            i = ends[i];
+        } else {
+            i += 1;
+        }
+    }
+
+    Ok(DeletionPlan { paths, freed })
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use crate::core::OutputLines;
+
+    use super::*;
+
+    const INPUT: Input = include_str!("../../input-test");
+
+    fn file_system(input: Input) -> FileSystem<'static> {
+        OutputLines::try_from(input)
+            .unwrap()
+            .reconstruct_file_system()
+            .unwrap()
+    }
+
+    fn brute_force(
+        file_system: &FileSystem,
+        free: usize,
+        constraints: &DeletionConstraints,
+    ) -> Option<usize> {
+        let entries: Vec<Entry> = file_system.walk().into_iter().skip(1).collect();
+
+        let allowed: Vec<&Entry> = entries
+            .iter()
+            .filter(|entry| {
+                (entry.is_file() && constraints.files
+                    || entry.is_directory() && constraints.directories)
+                    && constraints.protected.iter().all(|path| {
+                        path != &entry.path
+                            && !is_ancestor(path, &entry.path)
+                            && !is_ancestor(&entry.path, path)
+                    })
+            })
+            .collect();
+
+        (0..1usize << allowed.len())
+            .filter_map(|mask| {
+                let chosen: Vec<&&Entry> = allowed
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| mask & (1 << i) != 0)
+                    .map(|(_, entry)| entry)
+                    .collect();
+
+                let nested = chosen.iter().any(|a| {
+                    chosen
+                        .iter()
+                        .any(|b| a.path != b.path && is_ancestor(&a.path, &b.path))
+                });
+
+                let total: usize = chosen.iter().map(|entry| entry.size).sum();
+
+                (!nested && total >= free).then_some(total)
+            })
+            .min()
+    }
+
+    fn assert_optimal(free: usize, constraints: DeletionConstraints) {
+        let file_system = file_system(INPUT);
+        let plan = plan_deletion(&file_system, free, &constraints);
+
+        match brute_force(&file_system, free, &constraints) {
+            Some(expected) => {
+                let plan = plan.unwrap();
+                assert_eq!(plan.freed, expected);
+
+                let total: usize = plan
+                    .paths
+                    .iter()
+                    .map(|path| file_system.lookup(path).unwrap().size)
+                    .sum();
+                assert_eq!(total, plan.freed);
+
+                for a in plan.paths.iter() {
+                    assert!(constraints.protected.iter().all(|p| !is_ancestor(a, p)));
+                    for b in plan.paths.iter() {
+                        assert!(a == b || !is_ancestor(a, b));
+                    }
+                }
+            }
+            None => assert!(plan.is_err()),
+        }
+    }
+
+    #[test]
+    fn test_space_to_free() {
+        assert_eq!(
+            space_to_free(&file_system(INPUT), DISK_CAPACITY, UPDATE_SIZE),
+            8381165
+        );
+    }
+
+    #[test]
+    fn test_plan_deletion() {
+        let file_system = file_system(INPUT);
+
+        let plan = plan_deletion(&file_system, 8381165, &DeletionConstraints::default()).unwrap();
+        assert_eq!(plan.paths, ["/c.dat"]);
+        assert_eq!(plan.freed, 8504156);
+
+        let constraints = DeletionConstraints {
+            files: false,
+            ..Default::default()
+        };
+        let plan = plan_deletion(&file_system, 8381165, &constraints).unwrap();
+        assert_eq!(plan.paths, ["/d"]);
+    }
+
+    #[test]
+    fn test_plan_deletion_is_optimal() {
+        for free in [
+            1, 584, 600, 31_000, 95_000, 8381165, 20_000_000, 48381165, 48381166,
+        ] {
+            assert_optimal(free, DeletionConstraints::default());
+            assert_optimal(
+                free,
+                DeletionConstraints {
+                    protected: vec!["/c.dat".into(), "/a/e".into()],
+                    ..Default::default()
+                },
+            );
+            assert_optimal(
+                free,
+                DeletionConstraints {
+                    protected: vec!["/d/k".into()],
+                    files: false,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    #[test]
+    fn test_plan_deletion_input() {
+        let file_system = file_system(include_str!("../../input"));
+        let free = space_to_free(&file_system, DISK_CAPACITY, UPDATE_SIZE);
+
+        let plan = plan_deletion(&file_system, free, &DeletionConstraints::default()).unwrap();
+
+        assert!(plan.freed >= free);
+        assert!(plan.freed <= 272298);
+    }
+
+    #[test]
+    fn test_invalid_protected_path() {
+        let constraints = DeletionConstraints {
+            protected: vec!["/nope".into()],
+            ..Default::default()
+        };
+        assert!(plan_deletion(&file_system(INPUT), 1, &constraints).is_err());
+    }
+}
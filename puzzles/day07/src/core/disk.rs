@@ -0,0 +1,196 @@
+use std::fs;
+use std::path::{Component, Path};
+
+use anyhow::{bail, ensure, Context};
+
+use super::{FileSystem, OutputLines, ID, ROOT};
+
+pub fn export(file_system: &FileSystem, root: impl AsRef<Path>) -> anyhow::Result<()> {
+    let root = root.as_ref();
+
+    fs::create_dir_all(root).with_context(|| format!("unable to create {:?}", root))?;
+
+    for entry in file_system.walk().into_iter().skip(1) {
+        let path = Path::new(&entry.path).components().try_fold(
+            root.to_path_buf(),
+            |path, component| match component {
+                Component::RootDir => Ok(path),
+                Component::Normal(name) => Ok(path.join(name)),
+                _ => Err(anyhow::anyhow!("invalid path: {:?}", entry.path)),
+            },
+        )?;
+
+        ensure!(
+            path.starts_with(root),
+            "path escapes {:?}: {:?}",
+            root,
+            path
+        );
+
+        if entry.is_directory() {
+            fs::create_dir_all(&path).with_context(|| format!("unable to create {:?}", path))?;
+        } else {
+            // Extending an empty file leaves a hole, so no data blocks are written. Existing files
+            // are never overwritten.
+            fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+                .and_then(|file| file.set_len(entry.size as u64))
+                .with_context(|| format!("unable to create {:?}", path))?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn import(root: impl AsRef<Path>) -> anyhow::Result<FileSystem<'static>> {
+    fn visit(file_system: &mut FileSystem, id: ID, path: &Path) -> anyhow::Result<()> {
+        let entries = fs::read_dir(path).with_context(|| format!("unable to read {:?}", path))?;
+
+        for entry in entries {
+            let entry = entry.with_context(|| format!("unable to read {:?}", path))?;
+            let path = entry.path();
+
+            let name = entry
+                .file_name()
+                .into_string()
+                .map_err(|name| anyhow::anyhow!("invalid file name: {:?}", name))?;
+
+            let metadata = fs::symlink_metadata(&path)
+                .with_context(|| format!("unable to read metadata for {:?}", path))?;
+
+            if metadata.is_dir() {
+                let child_id = file_system
+                    .add_directory_in(id, name)
+                    .with_context(|| format!("unable to import {:?}", path))?;
+                visit(file_system, child_id, &path)?;
+            } else if metadata.is_file() {
+                file_system
+                    .add_file_in(id, name, metadata.len() as usize)
+                    .with_context(|| format!("unable to import {:?}", path))?;
+            } else {
+                bail!("unsupported file type: {:?}", path);
+            }
+        }
+
+        file_system.mark_listed(id);
+
+        Ok(())
+    }
+
+    let mut file_system = FileSystem::default();
+    visit(&mut file_system, ROOT, root.as_ref())?;
+    Ok(file_system)
+}
+
+pub fn import_transcript(root: impl AsRef<Path>) -> anyhow::Result<String> {
+    Ok(OutputLines::from_file_system(&import(root)?).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use super::*;
+
+    const INPUT: Input = include_str!("../../input-test");
+
+    #[test]
+    fn test_export() -> anyhow::Result<()> {
+        let file_system = OutputLines::try_from(INPUT)?.reconstruct_file_system()?;
+        let dir = tempfile::tempdir()?;
+
+        export(&file_system, dir.path())?;
+
+        assert!(dir.path().join("a/e").is_dir());
+        assert_eq!(fs::metadata(dir.path().join("b"))?.len(), 14848514);
+        assert_eq!(fs::metadata(dir.path().join("d/k"))?.len(), 7214296);
+        assert_eq!(fs::metadata(dir.path().join("a/e/i"))?.len(), 584);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_escape() -> anyhow::Result<()> {
+        assert!(OutputLines::try_from("$ cd /\n$ ls\ndir ..\n5 ../escaped.txt\n").is_err());
+
+        let mut file_system = FileSystem::default();
+        assert!(file_system.add_directory("..").is_err());
+        assert!(file_system.add_file("a/b", 5).is_err());
+
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().join("root");
+        file_system.add_directory("a")?;
+
+        export(&file_system, &root)?;
+        assert!(root.join("a").is_dir());
+        assert_eq!(fs::read_dir(dir.path())?.count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_existing() -> anyhow::Result<()> {
+        let file_system = OutputLines::try_from(INPUT)?.reconstruct_file_system()?;
+        let dir = tempfile::tempdir()?;
+
+        fs::create_dir(dir.path().join("d"))?;
+        fs::write(dir.path().join("d/k"), "keep")?;
+
+        assert!(export(&file_system, dir.path()).is_err());
+        assert_eq!(fs::read_to_string(dir.path().join("d/k"))?, "keep");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip() -> anyhow::Result<()> {
+        for input in [INPUT, include_str!("../../input")] {
+            let file_system = OutputLines::try_from(input)?.reconstruct_file_system()?;
+            let dir = tempfile::tempdir()?;
+
+            export(&file_system, dir.path())?;
+
+            let imported = import(dir.path())?;
+            assert_eq!(imported.tree(), file_system.tree());
+            assert_eq!(imported.size(), file_system.size());
+            assert!(imported.completeness()[&ROOT]);
+
+            let transcript = import_transcript(dir.path())?;
+            assert_eq!(
+                transcript,
+                OutputLines::from_file_system(&file_system).to_string()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_empty() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join("empty"))?;
+
+        assert_eq!(
+            import_transcript(dir.path())?,
+            "$ cd /\n$ ls\ndir empty\n$ cd empty\n$ ls\n"
+        );
+        assert!(import(dir.path().join("missing")).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_whitespace() -> anyhow::Result<()> {
+        for name in ["a b", "a\nb", " "] {
+            let dir = tempfile::tempdir()?;
+            fs::write(dir.path().join(name), "")?;
+
+            assert!(import(dir.path()).is_err(), "{:?}", name);
+            assert!(import_transcript(dir.path()).is_err(), "{:?}", name);
+        }
+
+        Ok(())
+    }
+}
@@ -1,18 +1,90 @@
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
 
-use anyhow::Context;
+use anyhow::{bail, ensure, Context};
 
-type ID = usize;
+pub type ID = usize;
+
+pub const ROOT: ID = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntryKind {
+    File,
+    Directory,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub id: ID,
+    pub path: String,
+    pub kind: EntryKind,
+    pub size: usize,
+}
+
+impl Entry {
+    pub fn name(&self) -> &str {
+        match self.path.rsplit_once('/') {
+            Some((_, "")) | None => "/",
+            Some((_, name)) => name,
+        }
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.kind == EntryKind::File
+    }
+
+    pub fn is_directory(&self) -> bool {
+        self.kind == EntryKind::Directory
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirectorySize {
+    pub size: usize,
+    pub complete: bool,
+}
+
+// Names are separated by whitespace in a transcript, so they cannot contain any.
+pub fn validate_name(name: &str) -> anyhow::Result<()> {
+    ensure!(
+        !matches!(name, "" | "." | "..") && !name.contains(|c: char| c == '/' || c.is_whitespace()),
+        "invalid name: {name:?}"
+    );
+    Ok(())
+}
+
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    fn matches(pattern: &[char], name: &[char]) -> bool {
+        match (pattern.first(), name.first()) {
+            (None, None) => true,
+            (Some('*'), _) => {
+                matches(&pattern[1..], name) || (!name.is_empty() && matches(pattern, &name[1..]))
+            }
+            (Some('?'), Some(_)) => matches(&pattern[1..], &name[1..]),
+            (Some(a), Some(b)) if a == b => matches(&pattern[1..], &name[1..]),
+            _ => false,
+        }
+    }
+
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    matches(&pattern, &name)
+}
 
 #[derive(Debug, Default)]
 pub struct FileSystem<'a> {
     ids: ID,
     current_directory: ID,
+    previous_directory: ID,
     files: HashSet<ID>,
     directories: HashSet<ID>,
+    listed: HashSet<ID>,
     sizes: HashMap<ID, usize>,
     parents: HashMap<ID, ID>,
-    children: HashMap<ID, HashMap<&'a str, ID>>,
+    names: HashMap<ID, Cow<'a, str>>,
+    children: HashMap<ID, HashMap<Cow<'a, str>, ID>>,
 }
 
 impl<'a> FileSystem<'a> {
@@ -21,100 +93,658 @@ impl<'a> FileSystem<'a> {
         self.ids
     }
 
-    pub fn set_directory(&mut self, name: &'a str) -> anyhow::Result<()> {
-        match name {
-            "/" => self.current_directory = 0,
-            ".." => {
-                if self.current_directory != 0 {
-                    self.current_directory = *self
-                        .parents
-                        .get(&self.current_directory)
-                        .with_context(|| format!("no parent directory: {name:?}"))?;
-                }
+    pub fn current_directory(&self) -> ID {
+        self.current_directory
+    }
+
+    pub fn kind(&self, id: ID) -> Option<EntryKind> {
+        if id == ROOT || self.directories.contains(&id) {
+            Some(EntryKind::Directory)
+        } else if self.files.contains(&id) {
+            Some(EntryKind::File)
+        } else {
+            None
+        }
+    }
+
+    pub fn child(&self, id: ID, name: &str) -> Option<ID> {
+        self.children.get(&id)?.get(name).copied()
+    }
+
+    pub fn list(&self, id: ID) -> Vec<(&str, ID)> {
+        let mut children: Vec<_> = self
+            .children
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .map(|(name, &id)| (name.as_ref(), id))
+            .collect();
+        children.sort_unstable();
+        children
+    }
+
+    pub fn resolve(&self, path: &str) -> anyhow::Result<ID> {
+        let mut id = if path.starts_with('/') {
+            ROOT
+        } else {
+            self.current_directory
+        };
+
+        for name in path.split('/') {
+            if self.kind(id) != Some(EntryKind::Directory) {
+                bail!("not a directory: {:?}", self.path(id));
             }
-            _ => {
-                let &child_id = self
-                    .children
-                    .entry(self.current_directory)
-                    .or_default()
-                    .get(name)
-                    .with_context(|| format!("no such directory: {name:?}"))?;
-
-                if self.files.contains(&child_id) {
-                    anyhow::bail!("not a directory: {name:?}");
-                }
 
-                self.current_directory = child_id;
+            match name {
+                "" | "." => {}
+                ".." => id = self.parents.get(&id).copied().unwrap_or(ROOT),
+                _ => {
+                    id = self
+                        .child(id, name)
+                        .with_context(|| format!("no such file or directory: {path:?}"))?
+                }
             }
         }
 
+        Ok(id)
+    }
+
+    pub fn path(&self, id: ID) -> String {
+        let mut names = Vec::new();
+        let mut id = id;
+
+        while let Some(&parent_id) = self.parents.get(&id) {
+            names.push(self.name(id));
+            id = parent_id;
+        }
+
+        names.reverse();
+
+        format!("/{}", names.join("/"))
+    }
+
+    pub fn entry(&self, id: ID) -> Option<Entry> {
+        Some(Entry {
+            id,
+            path: self.path(id),
+            kind: self.kind(id)?,
+            size: self.sizes.get(&id).copied().unwrap_or_default(),
+        })
+    }
+
+    pub fn lookup(&self, path: &str) -> anyhow::Result<Entry> {
+        let id = self.resolve(path)?;
+        self.entry(id)
+            .with_context(|| format!("no such file or directory: {path:?}"))
+    }
+
+    pub fn set_directory(&mut self, path: &str) -> anyhow::Result<()> {
+        let id = self.resolve(path)?;
+
+        if self.kind(id) != Some(EntryKind::Directory) {
+            bail!("not a directory: {path:?}");
+        }
+
+        self.previous_directory = self.current_directory;
+        self.current_directory = id;
+
         Ok(())
     }
 
-    pub fn clear_paths(&mut self) {
-        let size = *self.sizes.entry(self.current_directory).or_default();
+    pub fn set_previous_directory(&mut self) {
+        std::mem::swap(&mut self.current_directory, &mut self.previous_directory);
+    }
 
-        let mut id = self.current_directory;
+    fn adjust_sizes(&mut self, id: ID, size: usize, grow: bool) {
+        let mut id = Some(id);
 
-        loop {
-            self.sizes.entry(id).and_modify(|value| *value -= size);
-            if let Some(&parent_id) = self.parents.get(&id) {
-                id = parent_id;
+        while let Some(current) = id {
+            let value = self.sizes.entry(current).or_default();
+            if grow {
+                *value += size;
             } else {
-                break;
+                *value -= size;
             }
+            id = self.parents.get(&current).copied();
         }
+    }
 
-        for (_, child_id) in self
-            .children
-            .entry(self.current_directory)
+    fn detach(&mut self, id: ID) -> Option<Cow<'a, str>> {
+        let parent_id = *self.parents.get(&id)?;
+        let size = self.size_of(id);
+
+        self.adjust_sizes(parent_id, size, false);
+
+        let name = self.names.remove(&id)?;
+        self.children.get_mut(&parent_id)?.remove(&name);
+
+        Some(name)
+    }
+
+    fn attach(&mut self, parent_id: ID, name: Cow<'a, str>, id: ID) {
+        let size = self.size_of(id);
+
+        self.parents.insert(id, parent_id);
+        self.children
+            .entry(parent_id)
             .or_default()
-            .drain()
-        {
-            self.sizes.remove(&child_id);
-            self.parents.remove(&child_id);
-            self.files.remove(&child_id);
-            self.directories.remove(&child_id);
+            .insert(name.clone(), id);
+        self.names.insert(id, name);
+
+        self.adjust_sizes(parent_id, size, true);
+    }
+
+    fn remove_id(&mut self, id: ID) {
+        self.detach(id);
+
+        for entry in self.walk_from(id) {
+            self.sizes.remove(&entry.id);
+            self.parents.remove(&entry.id);
+            self.names.remove(&entry.id);
+            self.children.remove(&entry.id);
+            self.files.remove(&entry.id);
+            self.directories.remove(&entry.id);
+            self.listed.remove(&entry.id);
+        }
+
+        if self.kind(self.current_directory).is_none() {
+            self.current_directory = ROOT;
+        }
+
+        if self.kind(self.previous_directory).is_none() {
+            self.previous_directory = ROOT;
         }
     }
 
-    fn add_path(&mut self, name: &'a str) -> ID {
-        let id = self.new_id();
+    pub fn clear_directory(&mut self, id: ID) {
+        let children: Vec<_> = self.list(id).into_iter().map(|(_, id)| id).collect();
+
+        for child_id in children {
+            self.remove_id(child_id);
+        }
+    }
+
+    pub fn clear_paths(&mut self) {
+        self.clear_directory(self.current_directory);
+    }
 
-        self.parents.insert(id, self.current_directory);
+    fn add_path(&mut self, parent_id: ID, name: Cow<'a, str>) -> anyhow::Result<ID> {
+        validate_name(&name)?;
 
+        if let Some(existing) = self.child(parent_id, &name) {
+            self.remove_id(existing);
+        }
+
+        let id = self.new_id();
+
+        self.parents.insert(id, parent_id);
         self.children
-            .entry(self.current_directory)
+            .entry(parent_id)
             .or_default()
-            .insert(name, id);
+            .insert(name.clone(), id);
+        self.names.insert(id, name);
 
-        id
+        Ok(id)
     }
 
-    pub fn add_directory(&mut self, name: &'a str) {
-        let id = self.add_path(name);
+    pub fn add_directory_in(
+        &mut self,
+        parent_id: ID,
+        name: impl Into<Cow<'a, str>>,
+    ) -> anyhow::Result<ID> {
+        let id = self.add_path(parent_id, name.into())?;
         self.directories.insert(id);
+        Ok(id)
     }
 
-    pub fn add_file(&mut self, name: &'a str, size: usize) {
-        let mut id = self.add_path(name);
+    pub fn add_file_in(
+        &mut self,
+        parent_id: ID,
+        name: impl Into<Cow<'a, str>>,
+        size: usize,
+    ) -> anyhow::Result<ID> {
+        let id = self.add_path(parent_id, name.into())?;
 
         self.files.insert(id);
         self.sizes.insert(id, size);
+        self.adjust_sizes(parent_id, size, true);
+
+        Ok(id)
+    }
+
+    pub fn add_directory(&mut self, name: impl Into<Cow<'a, str>>) -> anyhow::Result<()> {
+        self.add_directory_in(self.current_directory, name)
+            .map(|_| ())
+    }
+
+    pub fn add_file(&mut self, name: impl Into<Cow<'a, str>>, size: usize) -> anyhow::Result<()> {
+        self.add_file_in(self.current_directory, name, size)
+            .map(|_| ())
+    }
+
+    fn resolve_parent(&self, path: &'a str) -> anyhow::Result<(ID, &'a str)> {
+        let (parent, name) = match path.trim_end_matches('/').rsplit_once('/') {
+            Some(("", name)) => ("/", name),
+            Some((parent, name)) => (parent, name),
+            None => (".", path),
+        };
 
-        while let Some(parent_id) = self.parents.get(&id) {
-            id = *parent_id;
-            *self.sizes.entry(id).or_default() += size;
+        validate_name(name).with_context(|| format!("invalid path: {path:?}"))?;
+
+        let parent_id = self.resolve(parent)?;
+
+        if self.kind(parent_id) != Some(EntryKind::Directory) {
+            bail!("not a directory: {parent:?}");
         }
+
+        Ok((parent_id, name))
+    }
+
+    pub fn make_directory(&mut self, path: &'a str) -> anyhow::Result<ID> {
+        let (parent_id, name) = self.resolve_parent(path)?;
+
+        if self.child(parent_id, name).is_some() {
+            bail!("file exists: {path:?}");
+        }
+
+        let id = self.add_directory_in(parent_id, name)?;
+        self.mark_listed(id);
+
+        Ok(id)
+    }
+
+    pub fn make_directories(&mut self, path: &'a str) -> anyhow::Result<ID> {
+        let mut id = if path.starts_with('/') {
+            ROOT
+        } else {
+            self.current_directory
+        };
+
+        for name in path.split('/') {
+            if self.kind(id) != Some(EntryKind::Directory) {
+                bail!("not a directory: {:?}", self.path(id));
+            }
+
+            id = match name {
+                "" | "." => id,
+                ".." => self.parents.get(&id).copied().unwrap_or(ROOT),
+                _ => match self.child(id, name) {
+                    Some(child_id) => child_id,
+                    None => self.add_directory_in(id, name)?,
+                },
+            };
+        }
+
+        if self.kind(id) != Some(EntryKind::Directory) {
+            bail!("not a directory: {path:?}");
+        }
+
+        Ok(id)
+    }
+
+    pub fn remove(&mut self, path: &str) -> anyhow::Result<()> {
+        let id = self.resolve(path)?;
+
+        if id == ROOT {
+            bail!("refusing to remove the root directory");
+        }
+
+        self.remove_id(id);
+
+        Ok(())
+    }
+
+    pub fn move_path(&mut self, from: &str, to: &'a str) -> anyhow::Result<()> {
+        let id = self.resolve(from)?;
+
+        if id == ROOT {
+            bail!("refusing to move the root directory");
+        }
+
+        let (parent_id, rename) = match self.resolve(to) {
+            Ok(target) if self.kind(target) == Some(EntryKind::Directory) => (target, None),
+            Ok(_) => bail!("file exists: {to:?}"),
+            Err(_) => {
+                let (parent_id, name) = self.resolve_parent(to)?;
+                (parent_id, Some(name))
+            }
+        };
+
+        let mut ancestor = Some(parent_id);
+        while let Some(current) = ancestor {
+            if current == id {
+                bail!("cannot move {from:?} into itself");
+            }
+            ancestor = self.parents.get(&current).copied();
+        }
+
+        let exists = match rename {
+            Some(name) => self.child(parent_id, name),
+            None => self.child(parent_id, self.name(id)),
+        };
+
+        if exists.is_some() {
+            bail!("file exists: {to:?}");
+        }
+
+        let name = self.detach(id).unwrap_or_default();
+        self.attach(parent_id, rename.map(Cow::Borrowed).unwrap_or(name), id);
+
+        Ok(())
+    }
+
+    pub fn name(&self, id: ID) -> &str {
+        self.names.get(&id).map_or("/", |name| name.as_ref())
+    }
+
+    pub fn size_of(&self, id: ID) -> usize {
+        self.sizes.get(&id).copied().unwrap_or_default()
     }
 
     pub fn size(&self) -> usize {
-        self.sizes[&0]
+        self.sizes.get(&ROOT).copied().unwrap_or_default()
+    }
+
+    pub fn set_file_size(&mut self, id: ID, size: usize) {
+        if !self.files.contains(&id) {
+            return;
+        }
+
+        let previous = self.size_of(id);
+
+        if let Some(&parent_id) = self.parents.get(&id) {
+            self.adjust_sizes(parent_id, previous, false);
+            self.adjust_sizes(parent_id, size, true);
+        }
+
+        self.sizes.insert(id, size);
+    }
+
+    pub fn mark_listed(&mut self, id: ID) {
+        self.listed.insert(id);
     }
 
-    pub fn directory_sizes(&self) -> impl Iterator<Item = &usize> {
-        self.directories
-            .iter()
-            .map(|id| self.sizes.get(id).unwrap())
+    pub fn is_listed(&self, id: ID) -> bool {
+        self.listed.contains(&id)
+    }
+
+    // Whether each directory and everything below it has been listed.
+    pub fn completeness(&self) -> HashMap<ID, bool> {
+        fn visit(fs: &FileSystem, id: ID, complete: &mut HashMap<ID, bool>) -> bool {
+            let mut result = fs.is_listed(id);
+
+            for (_, child_id) in fs.list(id) {
+                if fs.kind(child_id) == Some(EntryKind::Directory) {
+                    result &= visit(fs, child_id, complete);
+                }
+            }
+
+            complete.insert(id, result);
+            result
+        }
+
+        let mut complete = HashMap::new();
+        visit(self, ROOT, &mut complete);
+        complete
+    }
+
+    pub fn directory_sizes(&self) -> impl Iterator<Item = DirectorySize> + '_ {
+        let complete = self.completeness();
+
+        self.directories.iter().map(move |id| DirectorySize {
+            size: self.size_of(*id),
+            complete: complete.get(id).copied().unwrap_or_default(),
+        })
+    }
+
+    pub fn walk(&self) -> Vec<Entry> {
+        self.walk_from(ROOT)
+    }
+
+    pub fn walk_from(&self, id: ID) -> Vec<Entry> {
+        let mut entries = Vec::new();
+        let mut stack = vec![(id, self.path(id))];
+
+        while let Some((id, path)) = stack.pop() {
+            let Some(kind) = self.kind(id) else {
+                continue;
+            };
+
+            let prefix = path.trim_end_matches('/');
+            stack.extend(
+                self.list(id)
+                    .into_iter()
+                    .rev()
+                    .map(|(name, id)| (id, format!("{prefix}/{name}"))),
+            );
+
+            entries.push(Entry {
+                id,
+                path,
+                kind,
+                size: self.size_of(id),
+            });
+        }
+
+        entries
+    }
+
+    pub fn find<F>(&self, predicate: F) -> Vec<Entry>
+    where
+        F: Fn(&Entry) -> bool,
+    {
+        self.walk().into_iter().filter(predicate).collect()
+    }
+
+    pub fn find_by_name(&self, pattern: &str) -> Vec<Entry> {
+        self.find(|entry| matches_pattern(pattern, entry.name()))
+    }
+
+    pub fn du(&self) -> String {
+        fn visit(fs: &FileSystem, id: ID, output: &mut String) {
+            for (_, child_id) in fs.list(id) {
+                if fs.kind(child_id) == Some(EntryKind::Directory) {
+                    visit(fs, child_id, output);
+                }
+            }
+            let size = fs.sizes.get(&id).copied().unwrap_or_default();
+            writeln!(output, "{}\t{}", size, fs.path(id)).unwrap();
+        }
+
+        let mut output = String::new();
+        visit(self, ROOT, &mut output);
+        output
+    }
+
+    pub fn tree(&self) -> String {
+        fn visit(fs: &FileSystem, id: ID, name: &str, depth: usize, output: &mut String) {
+            let indent = "  ".repeat(depth);
+
+            match fs.kind(id) {
+                Some(EntryKind::File) => {
+                    let size = fs.sizes.get(&id).copied().unwrap_or_default();
+                    writeln!(output, "{indent}- {name} (file, size={size})").unwrap();
+                }
+                _ => {
+                    writeln!(output, "{indent}- {name} (dir)").unwrap();
+                    for (name, child_id) in fs.list(id) {
+                        visit(fs, child_id, name, depth + 1, output);
+                    }
+                }
+            }
+        }
+
+        let mut output = String::new();
+        visit(self, ROOT, "/", 0, &mut output);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use crate::core::OutputLines;
+
+    use super::*;
+
+    const INPUT: Input = include_str!("../../input-test");
+
+    fn file_system() -> FileSystem<'static> {
+        OutputLines::try_from(INPUT)
+            .unwrap()
+            .reconstruct_file_system()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("*.dat", "c.dat"));
+        assert!(matches_pattern("?", "e"));
+        assert!(matches_pattern("d*", "d.log"));
+        assert!(!matches_pattern("d*", "c.dat"));
+        assert!(!matches_pattern("?", "ab"));
+    }
+
+    #[test]
+    fn test_resolve() -> anyhow::Result<()> {
+        let fs = file_system();
+
+        assert_eq!(fs.resolve("/")?, ROOT);
+        assert_eq!(fs.path(fs.resolve("/a/e/i")?), "/a/e/i");
+        assert_eq!(fs.path(fs.resolve("/a/e/../../d/./k")?), "/d/k");
+        assert_eq!(fs.path(fs.resolve("../../..")?), "/");
+        assert!(fs.resolve("/a/x").is_err());
+        assert!(fs.resolve("/b/x").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lookup() -> anyhow::Result<()> {
+        let fs = file_system();
+
+        let entry = fs.lookup("/a/e")?;
+        assert_eq!(entry.name(), "e");
+        assert!(entry.is_directory());
+        assert_eq!(entry.size, 584);
+
+        let entry = fs.lookup("/d/d.log")?;
+        assert!(entry.is_file());
+        assert_eq!(entry.size, 8033020);
+
+        assert_eq!(fs.lookup("/")?.name(), "/");
+        assert_eq!(fs.lookup("/")?.size, 48381165);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_relative_set_directory() -> anyhow::Result<()> {
+        let mut fs = file_system();
+
+        fs.set_directory("/a")?;
+        fs.set_directory("e/..")?;
+        assert_eq!(fs.path(fs.current_directory()), "/a");
+        assert!(fs.set_directory("f").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_walk() {
+        let paths: Vec<_> = file_system()
+            .walk()
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect();
+
+        assert_eq!(
+            paths,
+            [
+                "/", "/a", "/a/e", "/a/e/i", "/a/f", "/a/g", "/a/h.lst", "/b", "/c.dat", "/d",
+                "/d/d.ext", "/d/d.log", "/d/j", "/d/k",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_walk_after_move() -> anyhow::Result<()> {
+        let mut fs = file_system();
+
+        fs.move_path("/a/e", "/d/x")?;
+        fs.remove("/a")?;
+
+        let paths: Vec<_> = fs.walk().into_iter().map(|entry| entry.path).collect();
+        assert_eq!(
+            paths,
+            [
+                "/", "/b", "/c.dat", "/d", "/d/d.ext", "/d/d.log", "/d/j", "/d/k", "/d/x",
+                "/d/x/i",
+            ]
+        );
+        assert_eq!(fs.name(fs.resolve("/d/x")?), "x");
+        assert_eq!(fs.path(fs.resolve("/d/x/i")?), "/d/x/i");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find() {
+        let fs = file_system();
+
+        let paths = |entries: Vec<Entry>| -> Vec<String> {
+            entries.into_iter().map(|entry| entry.path).collect()
+        };
+
+        assert_eq!(paths(fs.find_by_name("d*")), ["/d", "/d/d.ext", "/d/d.log"]);
+        assert_eq!(
+            paths(fs.find(|entry| entry.is_directory() && entry.size <= 100_000)),
+            ["/a", "/a/e"]
+        );
+    }
+
+    #[test]
+    fn test_du() {
+        assert_eq!(
+            file_system().du(),
+            "584\t/a/e\n94853\t/a\n24933642\t/d\n48381165\t/\n"
+        );
+    }
+
+    #[test]
+    fn test_tree() {
+        assert_eq!(
+            file_system().tree(),
+            "\
+- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+"
+        );
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        let mut file_system = FileSystem::default();
+
+        for name in ["", ".", "..", "a/b", "/", "a b", "a\nb", "\t"] {
+            assert!(file_system.add_directory(name).is_err(), "{:?}", name);
+            assert!(file_system.add_file(name, 1).is_err(), "{:?}", name);
+        }
+
+        assert!(file_system.add_directory("a").is_ok());
+        assert!(file_system.add_file("a.txt", 1).is_ok());
     }
 }
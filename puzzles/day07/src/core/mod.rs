@@ -3,3 +3,9 @@ pub use fs::*;
 
 pub mod terminal;
 pub use terminal::*;
+
+pub mod deletion;
+pub use deletion::*;
+
+pub mod disk;
+pub use disk::*;
@@ -1,5 +1,33 @@
+use std::fmt;
+
 #[derive(Debug, Clone)]
 pub enum Command<'a> {
-    ListCurrentDirectory,
-    ChangeDirectory { name: &'a str },
+    ListDirectory { path: Option<&'a str>, long: bool },
+    ChangeDirectory { path: &'a str },
+    ChangeToPreviousDirectory,
+    MakeDirectory { path: &'a str },
+    Remove { path: &'a str },
+    Move { from: &'a str, to: &'a str },
+}
+
+impl fmt::Display for Command<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ListDirectory { path, long } => {
+                write!(f, "ls")?;
+                if *long {
+                    write!(f, " -l")?;
+                }
+                if let Some(path) = path {
+                    write!(f, " {}", path)?;
+                }
+                Ok(())
+            }
+            Self::ChangeDirectory { path } => write!(f, "cd {}", path),
+            Self::ChangeToPreviousDirectory => write!(f, "cd -"),
+            Self::MakeDirectory { path } => write!(f, "mkdir {}", path),
+            Self::Remove { path } => write!(f, "rm {}", path),
+            Self::Move { from, to } => write!(f, "mv {} {}", from, to),
+        }
+    }
 }
@@ -0,0 +1,48 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    ConflictingKind {
+        path: String,
+    },
+    SizeChanged {
+        path: String,
+        before: usize,
+        after: usize,
+    },
+    UnlistedDirectory {
+        path: String,
+    },
+    UnexploredDirectory {
+        path: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub problem: Problem,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+
+        match &self.problem {
+            Problem::ConflictingKind { path } => {
+                write!(f, "{:?} is listed as both a file and a directory", path)
+            }
+            Problem::SizeChanged {
+                path,
+                before,
+                after,
+            } => write!(f, "size of {:?} changed from {} to {}", path, before, after),
+            Problem::UnlistedDirectory { path } => {
+                write!(f, "changed into {:?}, which was never listed", path)
+            }
+            Problem::UnexploredDirectory { path } => {
+                write!(f, "{:?} was never explored, so its size is unknown", path)
+            }
+        }
+    }
+}
@@ -1,5 +1,8 @@
 pub mod command;
 pub use command::*;
 
+pub mod diagnostic;
+pub use diagnostic::*;
+
 pub mod output;
 pub use output::*;
@@ -1,10 +1,12 @@
+use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::fmt;
 
-use anyhow::{anyhow, Context};
+use anyhow::{anyhow, ensure, Context};
 
-use crate::core::FileSystem;
+use crate::core::{validate_name, EntryKind, FileSystem, ID, ROOT};
 
-use super::Command;
+use super::{Command, Diagnostic, Problem};
 
 #[derive(Debug, Clone)]
 pub enum Output<'a> {
@@ -13,6 +15,98 @@ pub enum Output<'a> {
     File { name: &'a str, size: usize },
 }
 
+impl fmt::Display for Output<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Command(command) => write!(f, "$ {}", command),
+            Self::Directory { name } => write!(f, "dir {}", name),
+            Self::File { name, size } => write!(f, "{} {}", size, name),
+        }
+    }
+}
+
+fn is_mode(s: &str) -> bool {
+    s.len() == 10
+        && s.starts_with(['d', '-'])
+        && s.chars()
+            .skip(1)
+            .all(|c| matches!(c, 'r' | 'w' | 'x' | '-'))
+}
+
+fn parse_name(name: Option<&str>) -> anyhow::Result<&str> {
+    let name = name.context("missing name")?;
+    validate_name(name)?;
+    Ok(name)
+}
+
+fn check_flags(command: &str, flags: &[&str], allowed: &[&str]) -> anyhow::Result<()> {
+    for flag in flags {
+        ensure!(
+            allowed.contains(flag),
+            "unrecognized flag for {} command: {:?}",
+            command,
+            flag
+        );
+    }
+    Ok(())
+}
+
+fn parse_command<'a>(mut tokens: impl Iterator<Item = &'a str>) -> anyhow::Result<Command<'a>> {
+    let command = tokens.next().context("missing command")?;
+
+    let (flags, mut args): (Vec<_>, Vec<_>) =
+        tokens.partition(|token| token.starts_with('-') && *token != "-" && command != "cd");
+
+    args.reverse();
+
+    let mut arg = |name: &str| {
+        args.pop()
+            .with_context(|| format!("missing {} for {} command", name, command))
+    };
+
+    let command = match command {
+        "cd" => match arg("directory")? {
+            "-" => Command::ChangeToPreviousDirectory,
+            path => Command::ChangeDirectory { path },
+        },
+        "ls" => {
+            check_flags(command, &flags, &["-l"])?;
+            Command::ListDirectory {
+                path: args.pop(),
+                long: !flags.is_empty(),
+            }
+        }
+        "mkdir" => {
+            check_flags(command, &flags, &[])?;
+            Command::MakeDirectory {
+                path: arg("directory")?,
+            }
+        }
+        "rm" => {
+            // Removal is always recursive, so the usual recursive and force flags are accepted.
+            check_flags(command, &flags, &["-r", "-f", "-rf", "-fr"])?;
+            Command::Remove { path: arg("path")? }
+        }
+        "mv" => {
+            check_flags(command, &flags, &[])?;
+            Command::Move {
+                from: arg("source")?,
+                to: arg("destination")?,
+            }
+        }
+        _ => return Err(anyhow!("unrecognized command: {:?}", command)),
+    };
+
+    ensure!(
+        args.is_empty(),
+        "unexpected arguments for {} command: {:?}",
+        command,
+        args
+    );
+
+    Ok(command)
+}
+
 impl<'a> TryFrom<&'a str> for Output<'a> {
     type Error = anyhow::Error;
 
@@ -21,23 +115,26 @@ impl<'a> TryFrom<&'a str> for Output<'a> {
         let first = tokens.next().unwrap();
 
         if first == "$" {
-            let command = tokens.next().context("missing command")?;
-            if command == "cd" {
-                let name = tokens.next().context("missing directory for cd command")?;
-                Ok(Self::Command(Command::ChangeDirectory { name }))
-            } else if command == "ls" {
-                Ok(Self::Command(Command::ListCurrentDirectory))
-            } else {
-                Err(anyhow!("unrecognized command: {:?}", command))
-            }
+            Ok(Self::Command(parse_command(tokens)?))
         } else if first == "dir" {
-            let name = tokens.next().context("missing directory")?;
+            let name = parse_name(tokens.next()).context("invalid directory")?;
             Ok(Self::Directory { name })
+        } else if is_mode(first) {
+            let size = tokens.next().context("missing size")?;
+            let name = parse_name(tokens.next())?;
+            if first.starts_with('d') {
+                Ok(Self::Directory { name })
+            } else {
+                let size: usize = size
+                    .parse()
+                    .with_context(|| format!("invalid integer: {size:?}"))?;
+                Ok(Self::File { name, size })
+            }
         } else {
             let size: usize = first
                 .parse()
                 .with_context(|| format!("invalid integer: {first:?}"))?;
-            let name = tokens.next().context("missing file name")?;
+            let name = parse_name(tokens.next()).context("invalid file")?;
             Ok(Self::File { name, size })
         }
     }
@@ -47,32 +144,207 @@ impl<'a> TryFrom<&'a str> for Output<'a> {
 pub struct OutputLines<'a>(pub Vec<Output<'a>>);
 
 impl<'a> OutputLines<'a> {
-    pub fn reconstruct_file_system(&self) -> anyhow::Result<FileSystem<'a>> {
+    fn replay(
+        &self,
+        mut diagnostics: Option<&mut Vec<Diagnostic>>,
+    ) -> anyhow::Result<FileSystem<'a>> {
         let mut file_system: FileSystem = Default::default();
+        let mut listing = file_system.current_directory();
+        let mut first_seen: HashMap<ID, usize> = HashMap::from([(ROOT, 1)]);
+
+        let lenient = diagnostics.is_some();
+
+        // Without diagnostics, a listing that contradicts an earlier one is an error.
+        let mut report = |line: usize, problem: Problem| -> anyhow::Result<()> {
+            let diagnostic = Diagnostic { line, problem };
+            match diagnostics.as_mut() {
+                Some(diagnostics) => diagnostics.push(diagnostic),
+                None => ensure!(
+                    !matches!(
+                        diagnostic.problem,
+                        Problem::ConflictingKind { .. } | Problem::SizeChanged { .. }
+                    ),
+                    "{}",
+                    diagnostic
+                ),
+            }
+            Ok(())
+        };
 
         for (i, item) in self.0.iter().enumerate() {
+            let line = i + 1;
+
             match item {
-                Output::Command(command) => match command {
-                    Command::ChangeDirectory { name } => {
-                        file_system.set_directory(name).with_context(|| {
-                            format!("line {}, unable to change directory", i + 1)
-                        })?;
+                Output::Command(command) => {
+                    match command {
+                        Command::ChangeDirectory { path } => {
+                            match (file_system.resolve(path), lenient) {
+                                (Err(_), true) => {
+                                    let id = file_system.make_directories(path)?;
+                                    first_seen.entry(id).or_insert(line);
+                                    report(
+                                        line,
+                                        Problem::UnlistedDirectory {
+                                            path: file_system.path(id),
+                                        },
+                                    )?;
+                                    file_system.set_directory(path)
+                                }
+                                _ => file_system.set_directory(path),
+                            }
+                        }
+                        Command::ChangeToPreviousDirectory => {
+                            file_system.set_previous_directory();
+                            Ok(())
+                        }
+                        Command::ListDirectory { path, .. } => {
+                            let path = path.unwrap_or(".");
+                            file_system.resolve(path).and_then(|id| {
+                                ensure!(
+                                    file_system.kind(id) == Some(EntryKind::Directory),
+                                    "not a directory: {path:?}"
+                                );
+                                file_system.mark_listed(id);
+                                listing = id;
+                                Ok(())
+                            })
+                        }
+                        Command::MakeDirectory { path } => {
+                            file_system.make_directory(path).map(|id| {
+                                first_seen.insert(id, line);
+                            })
+                        }
+                        Command::Remove { path } => file_system.remove(path),
+                        Command::Move { from, to } => file_system.move_path(from, to),
                     }
-                    Command::ListCurrentDirectory => {
-                        file_system.clear_paths();
+                    .with_context(|| format!("line {}, unable to execute: {}", line, command))?;
+
+                    if !matches!(command, Command::ListDirectory { .. }) {
+                        listing = file_system.current_directory();
                     }
-                },
-                Output::Directory { name } => {
-                    file_system.add_directory(name);
-                }
-                Output::File { name, size } => {
-                    file_system.add_file(name, *size);
                 }
+                Output::Directory { name } => match file_system.child(listing, name) {
+                    Some(id) if file_system.kind(id) == Some(EntryKind::Directory) => {}
+                    existing => {
+                        if let Some(id) = existing {
+                            report(
+                                line,
+                                Problem::ConflictingKind {
+                                    path: file_system.path(id),
+                                },
+                            )?;
+                        }
+                        let id = file_system.add_directory_in(listing, *name)?;
+                        first_seen.insert(id, line);
+                    }
+                },
+                Output::File { name, size } => match file_system.child(listing, name) {
+                    Some(id) if file_system.kind(id) == Some(EntryKind::File) => {
+                        let before = file_system.size_of(id);
+                        if before != *size {
+                            report(
+                                line,
+                                Problem::SizeChanged {
+                                    path: file_system.path(id),
+                                    before,
+                                    after: *size,
+                                },
+                            )?;
+                            file_system.set_file_size(id, *size);
+                        }
+                    }
+                    existing => {
+                        if let Some(id) = existing {
+                            report(
+                                line,
+                                Problem::ConflictingKind {
+                                    path: file_system.path(id),
+                                },
+                            )?;
+                        }
+                        file_system.add_file_in(listing, *name, *size)?;
+                    }
+                },
+            }
+        }
+
+        for entry in file_system.walk() {
+            if entry.is_directory() && !file_system.is_listed(entry.id) {
+                report(
+                    first_seen.get(&entry.id).copied().unwrap_or(1),
+                    Problem::UnexploredDirectory { path: entry.path },
+                )?;
             }
         }
 
         Ok(file_system)
     }
+
+    pub fn reconstruct_file_system(&self) -> anyhow::Result<FileSystem<'a>> {
+        self.replay(None)
+    }
+
+    pub fn reconstruct_with_diagnostics(
+        &self,
+    ) -> anyhow::Result<(FileSystem<'a>, Vec<Diagnostic>)> {
+        let mut diagnostics = Vec::new();
+        let file_system = self.replay(Some(&mut diagnostics))?;
+        diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+        Ok((file_system, diagnostics))
+    }
+
+    pub fn validate(&self) -> anyhow::Result<Vec<Diagnostic>> {
+        self.reconstruct_with_diagnostics()
+            .map(|(_, diagnostics)| diagnostics)
+    }
+
+    pub fn from_file_system<'f>(file_system: &'f FileSystem) -> OutputLines<'f> {
+        fn visit<'f>(file_system: &'f FileSystem, id: ID, lines: &mut Vec<Output<'f>>) {
+            lines.push(Output::Command(Command::ListDirectory {
+                path: None,
+                long: false,
+            }));
+
+            let children = file_system.list(id);
+
+            for &(name, child_id) in children.iter() {
+                lines.push(match file_system.kind(child_id) {
+                    Some(EntryKind::Directory) => Output::Directory { name },
+                    _ => Output::File {
+                        name,
+                        size: file_system.size_of(child_id),
+                    },
+                });
+            }
+
+            for &(name, child_id) in children.iter() {
+                if file_system.kind(child_id) == Some(EntryKind::Directory) {
+                    lines.push(Output::Command(Command::ChangeDirectory { path: name }));
+                    visit(file_system, child_id, lines);
+                    lines.push(Output::Command(Command::ChangeDirectory { path: ".." }));
+                }
+            }
+        }
+
+        let mut lines = vec![Output::Command(Command::ChangeDirectory { path: "/" })];
+
+        visit(file_system, ROOT, &mut lines);
+
+        while let Some(Output::Command(Command::ChangeDirectory { path: ".." })) = lines.last() {
+            lines.pop();
+        }
+
+        OutputLines(lines)
+    }
+}
+
+impl fmt::Display for OutputLines<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in self.0.iter() {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
 }
 
 impl<'a> TryFrom<&'a str> for OutputLines<'a> {
@@ -92,3 +364,247 @@ impl<'a> TryFrom<&'a str> for OutputLines<'a> {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use super::*;
+
+    const INPUT: Input = include_str!("../../../input-test");
+
+    fn reconstruct(input: &str) -> anyhow::Result<FileSystem<'_>> {
+        OutputLines::try_from(input)?.reconstruct_file_system()
+    }
+
+    #[test]
+    fn test_round_trip() -> anyhow::Result<()> {
+        for input in [INPUT, include_str!("../../../input")] {
+            let file_system = reconstruct(input)?;
+            let transcript = OutputLines::from_file_system(&file_system).to_string();
+            assert_eq!(reconstruct(&transcript)?.tree(), file_system.tree());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_transcript() -> anyhow::Result<()> {
+        let transcript = OutputLines::from_file_system(&reconstruct(INPUT)?).to_string();
+        assert!(transcript
+            .starts_with("$ cd /\n$ ls\ndir a\n14848514 b\n8504156 c.dat\ndir d\n$ cd a\n"));
+        assert!(transcript
+            .ends_with("$ cd d\n$ ls\n5626152 d.ext\n8033020 d.log\n4060174 j\n7214296 k\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_extended_commands() -> anyhow::Result<()> {
+        let file_system = reconstruct(
+            "\
+$ cd /
+$ mkdir a
+$ mkdir a/b
+$ ls -l a/b
+-rw-r--r-- 100 x.txt
+drwxr-xr-x 4096 c
+$ cd a/b/c
+$ ls
+20 y
+$ cd -
+$ cd ../..
+$ mv a/b/x.txt a/z.txt
+$ mv a/b/c /
+$ rm -r a/b
+$ ls /c
+20 y
+7 w
+",
+        )?;
+
+        assert_eq!(
+            file_system.tree(),
+            "\
+- / (dir)
+  - a (dir)
+    - z.txt (file, size=100)
+  - c (dir)
+    - w (file, size=7)
+    - y (file, size=20)
+"
+        );
+        assert_eq!(file_system.size(), 127);
+        assert_eq!(file_system.lookup("/a")?.size, 100);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_commands() {
+        assert!(reconstruct("$ cd /\n$ rm /\n").is_err());
+        assert!(reconstruct("$ mkdir a\n$ mkdir a\n").is_err());
+        assert!(reconstruct("$ mkdir a\n$ mv a a/b\n").is_err());
+        assert!(reconstruct("$ ls -x\n").is_err());
+        assert!(reconstruct("$ cd\n").is_err());
+        assert!(reconstruct("$ mkdir a b\n").is_err());
+    }
+
+    #[test]
+    fn test_unrecognized_flags() {
+        for line in ["$ rm -zzz a", "$ mkdir -p a", "$ mv -f a b"] {
+            let error = OutputLines::try_from(line).unwrap_err();
+            assert!(
+                format!("{:#}", error).contains("unrecognized flag"),
+                "{:?}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        for line in [
+            "dir ..",
+            "dir .",
+            "dir a/b",
+            "5 ../escaped.txt",
+            "5 a/b",
+            "5 ..",
+        ] {
+            let input = format!("$ cd /\n$ ls\n{}\n", line);
+            assert!(OutputLines::try_from(input.as_str()).is_err(), "{:?}", line);
+        }
+
+        assert!(reconstruct("$ cd /\n$ mkdir a/..\n").is_err());
+        assert!(reconstruct("$ cd /\n$ mkdir a\n$ mv a ..\n").is_err());
+    }
+
+    const INCONSISTENT: &str = "\
+$ cd /
+$ ls
+dir a
+dir b
+10 x
+$ cd a
+$ ls
+5 y
+$ ls
+6 y
+$ cd ..
+$ ls
+dir x
+$ cd c
+$ ls
+1 z
+";
+
+    #[test]
+    fn test_validate() -> anyhow::Result<()> {
+        assert_eq!(OutputLines::try_from(INPUT)?.validate()?, []);
+
+        let diagnostics = OutputLines::try_from(INCONSISTENT)?.validate()?;
+
+        let path = |path: &str| path.to_string();
+
+        assert_eq!(
+            diagnostics,
+            [
+                Diagnostic {
+                    line: 4,
+                    problem: Problem::UnexploredDirectory { path: path("/b") },
+                },
+                Diagnostic {
+                    line: 10,
+                    problem: Problem::SizeChanged {
+                        path: path("/a/y"),
+                        before: 5,
+                        after: 6,
+                    },
+                },
+                Diagnostic {
+                    line: 13,
+                    problem: Problem::ConflictingKind { path: path("/x") },
+                },
+                Diagnostic {
+                    line: 13,
+                    problem: Problem::UnexploredDirectory { path: path("/x") },
+                },
+                Diagnostic {
+                    line: 14,
+                    problem: Problem::UnlistedDirectory { path: path("/c") },
+                },
+            ]
+        );
+
+        assert_eq!(
+            diagnostics[1].to_string(),
+            "line 10: size of \"/a/y\" changed from 5 to 6"
+        );
+
+        assert!(reconstruct(INCONSISTENT).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_strict_inconsistencies() {
+        for (input, message) in [
+            (
+                "$ cd /\n$ ls\n5 y\n$ ls\n6 y\n",
+                "line 5: size of \"/y\" changed from 5 to 6",
+            ),
+            (
+                "$ cd /\n$ ls\n5 y\n$ ls\ndir y\n",
+                "line 5: \"/y\" is listed as both a file and a directory",
+            ),
+            (
+                "$ cd /\n$ ls\ndir y\n$ ls\n5 y\n",
+                "line 5: \"/y\" is listed as both a file and a directory",
+            ),
+        ] {
+            let error = reconstruct(input).unwrap_err();
+            assert_eq!(error.to_string(), message, "{:?}", input);
+        }
+
+        assert!(reconstruct("$ cd /\n$ ls\n5 y\n$ ls\n5 y\n").is_ok());
+    }
+
+    #[test]
+    fn test_directory_sizes_completeness() -> anyhow::Result<()> {
+        let (file_system, _) =
+            OutputLines::try_from(INCONSISTENT)?.reconstruct_with_diagnostics()?;
+
+        let complete = file_system.completeness();
+
+        let mut sizes: Vec<_> = file_system
+            .find(|entry| entry.is_directory())
+            .into_iter()
+            .map(|entry| (entry.path, complete[&entry.id]))
+            .collect();
+        sizes.sort();
+
+        assert_eq!(
+            sizes,
+            [
+                (String::from("/"), false),
+                (String::from("/a"), true),
+                (String::from("/b"), false),
+                (String::from("/c"), true),
+                (String::from("/x"), false),
+            ]
+        );
+
+        let mut flags: Vec<_> = file_system
+            .directory_sizes()
+            .map(|directory| (directory.size, directory.complete))
+            .collect();
+        flags.sort();
+
+        assert_eq!(flags, [(0, false), (0, false), (1, true), (6, true)]);
+
+        assert!(reconstruct(INPUT)?
+            .directory_sizes()
+            .all(|directory| directory.complete));
+
+        Ok(())
+    }
+}
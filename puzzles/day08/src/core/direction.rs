@@ -4,12 +4,20 @@ use Direction::*;
 
 pub const DIRECTIONS: [Direction; 4] = [North, East, South, West];
 
+pub const ALL_DIRECTIONS: [Direction; 8] = [
+    North, NorthEast, East, SouthEast, South, SouthWest, West, NorthWest,
+];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     North,
+    NorthEast,
     East,
+    SouthEast,
     South,
+    SouthWest,
     West,
+    NorthWest,
 }
 
 impl Direction {
@@ -19,6 +27,10 @@ impl Direction {
             South => row.checked_add(1).map(|row| (row, column)),
             West => column.checked_sub(1).map(|column| (row, column)),
             East => column.checked_add(1).map(|column| (row, column)),
+            NorthEast => North.neighbor((row, column)).and_then(|l| East.neighbor(l)),
+            SouthEast => South.neighbor((row, column)).and_then(|l| East.neighbor(l)),
+            SouthWest => South.neighbor((row, column)).and_then(|l| West.neighbor(l)),
+            NorthWest => North.neighbor((row, column)).and_then(|l| West.neighbor(l)),
         }
     }
 }
@@ -0,0 +1,136 @@
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::Context;
+
+use super::{Grid, ScenicScores, Visibility};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heatmap {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl Heatmap {
+    pub fn from_grid<T, F>(grid: &Grid<T>, intensity: F) -> Self
+    where
+        F: Fn(&T) -> f64,
+    {
+        let (height, width) = grid.shape();
+
+        let values: Vec<f64> = grid
+            .row_iter()
+            .flat_map(|row| row.iter().map(&intensity).collect::<Vec<_>>())
+            .collect();
+
+        let max = values.iter().copied().fold(0.0, f64::max);
+
+        let pixels = values
+            .into_iter()
+            .map(|value| {
+                if max > 0.0 {
+                    (value / max * 255.0).round().clamp(0.0, 255.0) as u8
+                } else {
+                    0
+                }
+            })
+            .collect();
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn to_pgm(&self) -> Vec<u8> {
+        let mut bytes = format!("P5\n{} {}\n255\n", self.width, self.height).into_bytes();
+        bytes.extend_from_slice(&self.pixels);
+        bytes
+    }
+
+    pub fn to_png(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+
+        let mut encoder = png::Encoder::new(&mut bytes, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.pixels))
+            .context("unable to encode png")?;
+
+        Ok(bytes)
+    }
+
+    fn write(path: &Path, bytes: &[u8]) -> anyhow::Result<()> {
+        std::fs::File::create(path)
+            .and_then(|mut file| file.write_all(bytes))
+            .with_context(|| format!("unable to write {:?}", path))
+    }
+
+    pub fn write_pgm(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        Self::write(path.as_ref(), &self.to_pgm())
+    }
+
+    pub fn write_png(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        Self::write(path.as_ref(), &self.to_png()?)
+    }
+}
+
+impl From<&Visibility> for Heatmap {
+    fn from(visibility: &Visibility) -> Self {
+        Self::from_grid(&visibility.grid, |&visible| visible as u8 as f64)
+    }
+}
+
+impl From<&ScenicScores> for Heatmap {
+    fn from(scenic_scores: &ScenicScores) -> Self {
+        Self::from_grid(&scenic_scores.grid, |&score| score as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use crate::core::TreePatch;
+
+    use super::*;
+
+    const INPUT: Input = include_str!("../../input-test");
+
+    #[test]
+    fn test_visibility_pgm() -> anyhow::Result<()> {
+        let grid = TreePatch::try_from(INPUT)?.grid;
+        let pgm = Heatmap::from(&Visibility::from(&grid)).to_pgm();
+
+        let (header, pixels) = pgm.split_at(pgm.len() - 25);
+        assert_eq!(header, b"P5\n5 5\n255\n");
+        assert_eq!(&pixels[5..10], [255, 255, 255, 0, 255]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scenic_scores_png() -> anyhow::Result<()> {
+        let grid = TreePatch::try_from(INPUT)?.grid;
+        let heatmap = Heatmap::from(&ScenicScores::from(&grid));
+
+        assert_eq!(heatmap.pixels[3 * 5 + 2], 255);
+        assert_eq!(heatmap.pixels[0], 0);
+
+        let png = heatmap.to_png()?;
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+        let decoder = png::Decoder::new(png.as_slice());
+        let mut reader = decoder.read_info()?;
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut pixels)?;
+        assert_eq!(pixels, heatmap.pixels);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use super::{Direction, Location};
+
+pub fn bresenham(from: Location, to: Location) -> Vec<Location> {
+    let (mut row, mut column) = (from.0 as isize, from.1 as isize);
+    let (end_row, end_column) = (to.0 as isize, to.1 as isize);
+
+    let d_row = -(end_row - row).abs();
+    let d_column = (end_column - column).abs();
+    let step_row = if row < end_row { 1 } else { -1 };
+    let step_column = if column < end_column { 1 } else { -1 };
+
+    let mut error = d_column + d_row;
+    let mut line = Vec::new();
+
+    loop {
+        line.push((row as usize, column as usize));
+
+        if row == end_row && column == end_column {
+            break;
+        }
+
+        let e2 = 2 * error;
+
+        if e2 >= d_row {
+            error += d_row;
+            column += step_column;
+        }
+
+        if e2 <= d_column {
+            error += d_column;
+            row += step_row;
+        }
+    }
+
+    line
+}
+
+pub fn ray(
+    from: Location,
+    direction: Direction,
+    (height, width): (usize, usize),
+) -> impl Iterator<Item = Location> {
+    std::iter::successors(Some(from), move |&location| direction.neighbor(location))
+        .skip(1)
+        .take_while(move |&(row, column)| row < height && column < width)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::Direction::*;
+
+    use super::*;
+
+    #[test]
+    fn test_bresenham() {
+        assert_eq!(bresenham((0, 0), (0, 3)), [(0, 0), (0, 1), (0, 2), (0, 3)]);
+        assert_eq!(bresenham((2, 2), (0, 0)), [(2, 2), (1, 1), (0, 0)]);
+        assert_eq!(
+            bresenham((0, 0), (1, 4)),
+            [(0, 0), (0, 1), (1, 2), (1, 3), (1, 4)]
+        );
+        assert_eq!(bresenham((3, 1), (3, 1)), [(3, 1)]);
+    }
+
+    #[test]
+    fn test_ray() {
+        assert_eq!(ray((1, 1), NorthWest, (5, 5)).collect::<Vec<_>>(), [(0, 0)]);
+        assert_eq!(
+            ray((1, 1), SouthEast, (4, 5)).collect::<Vec<_>>(),
+            [(2, 2), (3, 3)]
+        );
+        assert_eq!(ray((0, 4), East, (5, 5)).count(), 0);
+    }
+}
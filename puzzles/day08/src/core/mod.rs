@@ -13,3 +13,12 @@ pub use visibility::*;
 
 pub mod scenic_score;
 pub use scenic_score::*;
+
+pub mod line;
+pub use line::*;
+
+pub mod viewshed;
+pub use viewshed::*;
+
+pub mod heatmap;
+pub use heatmap::*;
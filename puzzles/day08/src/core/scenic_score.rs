@@ -1,14 +1,67 @@
-use super::{Direction, Grid, Location, DIRECTIONS};
+use super::{Grid, Location};
 
 pub type ScenicScore = usize;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ScenicScores {
     pub grid: Grid<ScenicScore>,
 }
 
 impl<T: PartialOrd> From<&Grid<T>> for ScenicScores {
     fn from(grid: &Grid<T>) -> Self {
+        // The stack holds the positions of trees that are not yet blocked by a later tree, so their
+        // heights are non-increasing, and each tree is pushed and popped at most once per line.
+        fn scan_line<T: PartialOrd>(
+            results: &mut Grid<ScenicScore>,
+            grid: &Grid<T>,
+            line: impl Iterator<Item = Location>,
+            stack: &mut Vec<usize>,
+        ) {
+            let line: Vec<Location> = line.collect();
+            stack.clear();
+
+            for (i, &location) in line.iter().enumerate() {
+                while let Some(&j) = stack.last() {
+                    if grid[line[j]] >= grid[location] {
+                        break;
+                    }
+                    stack.pop();
+                }
+
+                results[location] *= stack.last().map_or(i, |&j| i - j);
+                stack.push(i);
+            }
+        }
+
+        let (height, width) = grid.shape();
+        let mut results: Grid<ScenicScore> = Grid::from_element(height, width, 1);
+        let mut stack = Vec::with_capacity(height.max(width));
+
+        for row in 0..height {
+            let line = (0..width).map(|column| (row, column));
+            scan_line(&mut results, grid, line.clone(), &mut stack);
+            scan_line(&mut results, grid, line.rev(), &mut stack);
+        }
+
+        for column in 0..width {
+            let line = (0..height).map(|row| (row, column));
+            scan_line(&mut results, grid, line.clone(), &mut stack);
+            scan_line(&mut results, grid, line.rev(), &mut stack);
+        }
+
+        Self { grid: results }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use crate::core::{Direction, TreePatch, DIRECTIONS};
+
+    use super::*;
+
+    fn naive<T: PartialOrd>(grid: &Grid<T>) -> ScenicScores {
         let (height, width) = grid.shape();
         let mut results: Grid<ScenicScore> = Grid::from_element(height, width, 1);
 
@@ -38,6 +91,23 @@ impl<T: PartialOrd> From<&Grid<T>> for ScenicScores {
             }
         }
 
-        Self { grid: results }
+        ScenicScores { grid: results }
+    }
+
+    #[test]
+    fn test_matches_naive() -> anyhow::Result<()> {
+        let inputs: [Input; 4] = [
+            include_str!("../../input-test"),
+            include_str!("../../input"),
+            "5",
+            "11111\n12321\n11111\n",
+        ];
+
+        for input in inputs {
+            let grid = TreePatch::try_from(input)?.grid;
+            assert_eq!(ScenicScores::from(&grid), naive(&grid));
+        }
+
+        Ok(())
     }
 }
@@ -1,6 +1,6 @@
 use anyhow::{anyhow, ensure, Context};
 
-use super::{Grid, Location, ScenicScore, ScenicScores, Visibility};
+use super::{can_see, Grid, Location, Observer, ScenicScore, ScenicScores, Viewshed, Visibility};
 
 pub type Height = u8;
 
@@ -31,6 +31,14 @@ impl TreePatch {
             .max()
             .unwrap_or_default()
     }
+
+    pub fn viewshed(&self, observer: &Observer) -> anyhow::Result<Viewshed> {
+        Viewshed::new(&self.grid, observer)
+    }
+
+    pub fn can_see(&self, from: Location, to: Location, eye_height: f64) -> anyhow::Result<bool> {
+        can_see(&self.grid, &Observer::new(from, eye_height), to)
+    }
 }
 
 impl TryFrom<&str> for TreePatch {
@@ -0,0 +1,245 @@
+use anyhow::ensure;
+
+use super::{bresenham, ray, Direction, Grid, Location};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observer {
+    pub location: Location,
+    pub eye_height: f64,
+}
+
+impl Observer {
+    pub fn new(location: Location, eye_height: f64) -> Self {
+        Self {
+            location,
+            eye_height,
+        }
+    }
+
+    fn check<T>(&self, grid: &Grid<T>) -> anyhow::Result<()> {
+        check_location(grid, self.location)
+    }
+
+    fn elevation<T: Copy + Into<f64>>(&self, grid: &Grid<T>) -> f64 {
+        grid[self.location].into() + self.eye_height
+    }
+
+    // The slope from the observer's eye to the top of the tree at the given location.
+    fn slope<T: Copy + Into<f64>>(&self, grid: &Grid<T>, location: Location) -> f64 {
+        let d_row = location.0 as f64 - self.location.0 as f64;
+        let d_column = location.1 as f64 - self.location.1 as f64;
+        let distance = d_row.hypot(d_column);
+        (grid[location].into() - self.elevation(grid)) / distance
+    }
+}
+
+fn check_location<T>(grid: &Grid<T>, (row, column): Location) -> anyhow::Result<()> {
+    let (height, width) = grid.shape();
+    ensure!(
+        row < height && column < width,
+        "location {:?} is outside of the {}x{} grid",
+        (row, column),
+        height,
+        width
+    );
+    Ok(())
+}
+
+pub fn can_see<T: Copy + Into<f64>>(
+    grid: &Grid<T>,
+    observer: &Observer,
+    target: Location,
+) -> anyhow::Result<bool> {
+    observer.check(grid)?;
+    check_location(grid, target)?;
+
+    if target == observer.location {
+        return Ok(true);
+    }
+
+    let line = bresenham(observer.location, target);
+    let target_slope = observer.slope(grid, target);
+
+    Ok(line[1..line.len() - 1]
+        .iter()
+        .all(|&location| observer.slope(grid, location) < target_slope))
+}
+
+#[derive(Debug, Clone)]
+pub struct Viewshed {
+    pub observer: Observer,
+    pub grid: Grid<bool>,
+}
+
+impl Viewshed {
+    fn empty<T>(grid: &Grid<T>, observer: &Observer) -> anyhow::Result<Self> {
+        observer.check(grid)?;
+
+        let (height, width) = grid.shape();
+        let mut results = Grid::from_element(height, width, false);
+        results[observer.location] = true;
+
+        Ok(Self {
+            observer: *observer,
+            grid: results,
+        })
+    }
+
+    fn scan<T: Copy + Into<f64>>(&mut self, grid: &Grid<T>, line: impl Iterator<Item = Location>) {
+        let mut max_slope = f64::NEG_INFINITY;
+
+        for location in line {
+            let slope = self.observer.slope(grid, location);
+            if slope > max_slope {
+                self.grid[location] = true;
+                max_slope = slope;
+            }
+        }
+    }
+
+    pub fn new<T: Copy + Into<f64>>(grid: &Grid<T>, observer: &Observer) -> anyhow::Result<Self> {
+        let mut viewshed = Self::empty(grid, observer)?;
+
+        let (height, width) = grid.shape();
+
+        for row in 0..height {
+            for column in 0..width {
+                viewshed.grid[(row, column)] = can_see(grid, observer, (row, column))?;
+            }
+        }
+
+        Ok(viewshed)
+    }
+
+    pub fn along_directions<T: Copy + Into<f64>>(
+        grid: &Grid<T>,
+        observer: &Observer,
+        directions: &[Direction],
+    ) -> anyhow::Result<Self> {
+        let mut viewshed = Self::empty(grid, observer)?;
+
+        for &direction in directions {
+            viewshed.scan(grid, ray(observer.location, direction, grid.shape()));
+        }
+
+        Ok(viewshed)
+    }
+
+    pub fn along_rays<T: Copy + Into<f64>>(
+        grid: &Grid<T>,
+        observer: &Observer,
+        ends: &[Location],
+    ) -> anyhow::Result<Self> {
+        let mut viewshed = Self::empty(grid, observer)?;
+
+        for &end in ends {
+            check_location(grid, end)?;
+            viewshed.scan(grid, bresenham(observer.location, end).into_iter().skip(1));
+        }
+
+        Ok(viewshed)
+    }
+
+    pub fn is_visible(&self, location: Location) -> bool {
+        self.grid.get(location).copied().unwrap_or_default()
+    }
+
+    pub fn count(&self) -> usize {
+        self.grid.iter().filter(|&&visible| visible).count()
+    }
+}
+
+impl std::fmt::Display for Viewshed {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for row in self.grid.row_iter() {
+            for value in row.iter() {
+                write!(f, "{}", *value as u8)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use crate::core::{TreePatch, ALL_DIRECTIONS, DIRECTIONS};
+
+    use super::*;
+
+    const INPUT: Input = include_str!("../../input-test");
+
+    fn grid() -> Grid<u8> {
+        TreePatch::try_from(INPUT).unwrap().grid
+    }
+
+    #[test]
+    fn test_can_see() -> anyhow::Result<()> {
+        let grid = grid();
+        let observer = Observer::new((3, 2), 0.0);
+
+        assert!(can_see(&grid, &observer, (3, 1))?);
+        assert!(can_see(&grid, &observer, (3, 0))?);
+        assert!(can_see(&grid, &observer, (1, 2))?);
+        assert!(!can_see(&grid, &observer, (0, 2))?);
+        assert!(can_see(&grid, &Observer::new((3, 2), 10.0), (0, 2))?);
+
+        assert!(can_see(&grid, &observer, (5, 0)).is_err());
+        assert!(can_see(&grid, &Observer::new((0, 9), 0.0), (0, 0)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_along_directions() -> anyhow::Result<()> {
+        let grid = grid();
+        let observer = Observer::new((3, 2), 0.0);
+
+        let viewshed = Viewshed::along_directions(&grid, &observer, &DIRECTIONS)?;
+        assert_eq!(viewshed.to_string(), "00000\n00100\n00100\n11111\n00100\n");
+
+        let viewshed = Viewshed::along_directions(&grid, &observer, &ALL_DIRECTIONS)?;
+        assert!(viewshed.is_visible((4, 1)));
+        assert!(viewshed.is_visible((2, 3)));
+        assert!(!viewshed.is_visible((0, 0)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_viewshed() -> anyhow::Result<()> {
+        let grid = grid();
+
+        for (location, eye_height, expected) in [
+            ((3, 2), 0.0, "00011\n00101\n11111\n11111\n01110\n"),
+            ((0, 0), 0.0, "11110\n11000\n10000\n00000\n00000\n"),
+            ((2, 2), 1.5, "00011\n01111\n11111\n11111\n11010\n"),
+            ((4, 4), 0.0, "00000\n00000\n00000\n00011\n00011\n"),
+        ] {
+            let viewshed = Viewshed::new(&grid, &Observer::new(location, eye_height))?;
+            assert_eq!(viewshed.to_string(), expected, "{:?}", location);
+        }
+
+        let viewshed = Viewshed::new(&grid, &Observer::new((2, 2), 100.0))?;
+        assert_eq!(viewshed.count(), 25);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_along_rays() -> anyhow::Result<()> {
+        let grid = grid();
+        let observer = Observer::new((3, 2), 0.0);
+
+        let viewshed = Viewshed::along_rays(&grid, &observer, &[(0, 2), (0, 0)])?;
+        assert!(viewshed.is_visible((1, 2)));
+        assert!(!viewshed.is_visible((0, 2)));
+        assert!(viewshed.is_visible((2, 1)));
+
+        assert!(Viewshed::along_rays(&grid, &observer, &[(9, 9)]).is_err());
+
+        Ok(())
+    }
+}
@@ -6,22 +6,22 @@ type Solution = usize;
 pub type Solution1 = Solution;
 pub type Solution2 = Solution;
 
-fn trail_size(moves: &[Movement], num_knots: usize) -> usize {
-    let mut rope = Rope::new(num_knots);
+fn trail_size<const D: usize>(moves: &[Movement], num_knots: usize) -> anyhow::Result<usize> {
+    let mut rope = Rope::<D>::new(num_knots);
 
     for vector in moves.iter() {
-        rope.drag(vector);
+        rope.drag(vector)?;
     }
 
-    rope.trail.len()
+    Ok(rope.trail().map_or(0, |trail| trail.len()))
 }
 
 pub fn solve1(vectors: &Parsed1) -> anyhow::Result<Solution1> {
-    Ok(trail_size(vectors, 2))
+    trail_size::<2>(vectors, 2)
 }
 
 pub fn solve2(vectors: &Parsed2) -> anyhow::Result<Solution2> {
-    Ok(trail_size(vectors, 10))
+    trail_size::<2>(vectors, 10)
 }
 
 #[cfg(test)]
@@ -46,4 +46,18 @@ pub mod tests {
         assert_eq!(solve2(&parse2(INPUT2)?)?, 36);
         Ok(())
     }
+
+    #[test]
+    fn test_three_dimensions_match() -> anyhow::Result<()> {
+        for input in [INPUT1, INPUT2, include_str!("../../input")] {
+            let moves = parse1(input)?;
+            for num_knots in [2, 10] {
+                assert_eq!(
+                    trail_size::<3>(&moves, num_knots)?,
+                    trail_size::<2>(&moves, num_knots)?
+                );
+            }
+        }
+        Ok(())
+    }
 }
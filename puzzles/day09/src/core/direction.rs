@@ -1,3 +1,5 @@
+use std::fmt;
+
 use anyhow::{anyhow, ensure};
 
 use Direction::*;
@@ -8,6 +10,48 @@ pub enum Direction {
     Down,
     Left,
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+    Forward,
+    Back,
+}
+
+impl Direction {
+    pub fn offset(&self) -> [isize; 3] {
+        match self {
+            Up => [0, 1, 0],
+            Down => [0, -1, 0],
+            Left => [-1, 0, 0],
+            Right => [1, 0, 0],
+            UpLeft => [-1, 1, 0],
+            UpRight => [1, 1, 0],
+            DownLeft => [-1, -1, 0],
+            DownRight => [1, -1, 0],
+            Forward => [0, 0, 1],
+            Back => [0, 0, -1],
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Up => "U",
+            Down => "D",
+            Left => "L",
+            Right => "R",
+            UpLeft => "UL",
+            UpRight => "UR",
+            DownLeft => "DL",
+            DownRight => "DR",
+            Forward => "F",
+            Back => "B",
+        };
+
+        write!(f, "{}", s)
+    }
 }
 
 impl TryFrom<char> for Direction {
@@ -19,6 +63,8 @@ impl TryFrom<char> for Direction {
             'D' => Ok(Down),
             'L' => Ok(Left),
             'R' => Ok(Right),
+            'F' => Ok(Forward),
+            'B' => Ok(Back),
             _ => Err(anyhow!("invalid direction: {:?}", s)),
         }
     }
@@ -28,7 +74,44 @@ impl TryFrom<&str> for Direction {
     type Error = anyhow::Error;
 
     fn try_from(s: &str) -> Result<Self, Self::Error> {
-        ensure!(s.len() == 1, "expected direction to be a single character");
-        s.chars().next().unwrap().try_into()
+        match s {
+            "UL" | "LU" => Ok(UpLeft),
+            "UR" | "RU" => Ok(UpRight),
+            "DL" | "LD" => Ok(DownLeft),
+            "DR" | "RD" => Ok(DownRight),
+            _ => {
+                ensure!(s.len() == 1, "invalid direction: {:?}", s);
+                s.chars().next().unwrap().try_into()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> anyhow::Result<()> {
+        assert_eq!(Direction::try_from("U")?, Up);
+        assert_eq!(Direction::try_from("UL")?, UpLeft);
+        assert_eq!(Direction::try_from("RD")?, DownRight);
+        assert_eq!(Direction::try_from("F")?, Forward);
+        assert!(Direction::try_from("UD").is_err());
+        assert!(Direction::try_from("").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_display() -> anyhow::Result<()> {
+        for direction in [
+            Up, Down, Left, Right, UpLeft, UpRight, DownLeft, DownRight, Forward, Back,
+        ] {
+            assert_eq!(
+                Direction::try_from(direction.to_string().as_str())?,
+                direction
+            );
+        }
+        Ok(())
     }
 }
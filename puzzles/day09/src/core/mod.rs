@@ -1,4 +1,6 @@
-pub type Point = nalgebra::Point2<isize>;
+pub type Point<const D: usize = 2> = nalgebra::Point<isize, D>;
+pub type Point2 = Point<2>;
+pub type Point3 = Point<3>;
 
 pub mod direction;
 pub use direction::*;
@@ -8,3 +10,8 @@ pub use movement::*;
 
 pub mod rope;
 pub use rope::*;
+
+pub mod stats;
+pub use stats::*;
+
+pub mod render;
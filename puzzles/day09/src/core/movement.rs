@@ -4,8 +4,6 @@ use anyhow::Context;
 
 use super::Direction;
 
-use Direction::*;
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Movement {
     pub direction: Direction,
@@ -23,19 +21,7 @@ impl Movement {
 
 impl fmt::Display for Movement {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{} {}",
-            match self.direction {
-                Up => 'U',
-                Down => 'D',
-                Left => 'L',
-                Right => 'R',
-            },
-            self.magnitude
-        )?;
-
-        Ok(())
+        write!(f, "{} {}", self.direction, self.magnitude)
     }
 }
 
@@ -58,3 +44,19 @@ impl TryFrom<&str> for Movement {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::core::Direction::*;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() -> anyhow::Result<()> {
+        assert_eq!(Movement::try_from("R 4")?, Movement::new(Right, 4));
+        assert_eq!(Movement::try_from("DL 2")?, Movement::new(DownLeft, 2));
+        assert_eq!(Movement::new(UpRight, 7).to_string(), "UR 7");
+        assert!(Movement::try_from("X 1").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt;
+
+use super::{Point2, Rope};
+
+fn label(knot: usize, size: usize) -> char {
+    match knot {
+        0 => 'H',
+        _ if size == 2 => 'T',
+        1..=9 => char::from_digit(knot as u32, 10).unwrap(),
+        _ => 'T',
+    }
+}
+
+fn bounds<'a>(points: impl Iterator<Item = &'a Point2>) -> (Point2, Point2) {
+    let origin = Point2::default();
+    points.fold((origin, origin), |(min, max), point| {
+        (min.inf(point), max.sup(point))
+    })
+}
+
+impl Rope<2> {
+    fn render_with<F>(&self, min: Point2, max: Point2, cell: F) -> String
+    where
+        F: Fn(Point2) -> char,
+    {
+        let mut s = String::new();
+
+        for y in (min.y..=max.y).rev() {
+            for x in min.x..=max.x {
+                s.push(cell(Point2::new(x, y)));
+            }
+            s.push('\n');
+        }
+
+        s
+    }
+
+    // Knots are drawn over the start, which is drawn over the trail of the last knot.
+    pub fn render(&self) -> String {
+        let trail = self.trail();
+        let (min, max) = bounds(self.knots.iter().chain(trail.into_iter().flatten()));
+
+        self.render_with(min, max, |point| {
+            match self.knots.iter().position(|knot| knot == &point) {
+                Some(knot) => label(knot, self.knots.len()),
+                None if point == Point2::default() => 's',
+                None if trail.is_some_and(|trail| trail.contains(&point)) => '#',
+                None => '.',
+            }
+        })
+    }
+}
+
+impl fmt::Display for Rope<2> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use crate::answer::parse1;
+    use crate::core::{Direction, Movement};
+
+    use super::*;
+
+    fn drag(rope: &mut Rope<2>, input: Input) -> anyhow::Result<()> {
+        for movement in parse1(input)?.iter() {
+            rope.drag(movement)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_render() -> anyhow::Result<()> {
+        let mut rope = Rope::<2>::new(10);
+        assert_eq!(rope.to_string(), "H\n");
+
+        rope.drag(&Movement::new(Direction::Right, 4))?;
+        assert_eq!(rope.to_string(), "4321H\n");
+
+        rope.drag(&Movement::new(Direction::Up, 4))?;
+        assert_eq!(rope.to_string(), "....H\n....1\n..432\n.5...\n6....\n");
+
+        let mut rope = Rope::<2>::new(2);
+        rope.drag(&Movement::new(Direction::Right, 2))?;
+        assert_eq!(rope.to_string(), "sTH\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_trail() -> anyhow::Result<()> {
+        let mut rope = Rope::<2>::new(2);
+        drag(&mut rope, include_str!("../../input-test-1"))?;
+
+        assert_eq!(rope.render(), "..##.\n...##\n.TH##\n....#\ns###.\n");
+
+        let mut rope = Rope::<2>::new(2);
+        rope.drag(&Movement::new(Direction::Right, 5))?;
+        assert_eq!(rope.render(), "s###TH\n");
+
+        rope.drag(&Movement::new(Direction::Left, 7))?;
+        assert_eq!(rope.render(), "HTs####\n");
+
+        Ok(())
+    }
+}
@@ -1,92 +1,193 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use super::{Direction, Movement, Point};
+use anyhow::ensure;
 
-use Direction::*;
+use super::{Direction, Movement, Point, TrailStats, Visit};
 
-#[derive(Debug)]
-pub struct Rope {
-    pub knots: Vec<Point>,
-    pub trail: HashSet<Point>,
+// In three dimensions, four-way following moves along a single axis, and eight-way following
+// moves along any of the 26 neighboring offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FollowRule {
+    FourWay,
+    #[default]
+    EightWay,
 }
 
-fn rotate(point: Point, direction: &Direction) -> Point {
-    let (x, y) = match direction {
-        Up => (0, 1),
-        Down => (0, -1),
-        Left => (-1, 0),
-        Right => (1, 0),
-    };
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RopePhysics {
+    pub slack: usize,
+    pub follow: FollowRule,
+}
 
-    Point::new(point.x + x, point.y + y)
+impl Default for RopePhysics {
+    fn default() -> Self {
+        Self {
+            slack: 1,
+            follow: FollowRule::default(),
+        }
+    }
 }
 
-fn adjust_tail(head: Point, mut tail: Point) -> Point {
-    let diff = head - tail;
-    let dist = diff.abs();
+impl RopePhysics {
+    fn distance<const D: usize>(&self, head: Point<D>, tail: Point<D>) -> usize {
+        let dist = (head - tail).abs();
 
-    if dist.x > 1 || dist.y > 1 {
-        tail += (head - tail).map(|c| c.signum());
+        match self.follow {
+            FollowRule::FourWay => dist.sum() as usize,
+            FollowRule::EightWay => dist.max() as usize,
+        }
+    }
+
+    fn step<const D: usize>(&self, head: Point<D>, mut tail: Point<D>) -> Point<D> {
+        let diff = head - tail;
+
+        match self.follow {
+            FollowRule::FourWay => {
+                let axis = diff.iamax();
+                tail[axis] += diff[axis].signum();
+                tail
+            }
+            FollowRule::EightWay => tail + diff.map(|c| c.signum()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Rope<const D: usize = 2> {
+    pub physics: RopePhysics,
+    pub knots: Vec<Point<D>>,
+    pub trails: Vec<HashSet<Point<D>>>,
+    pub visits: Vec<HashMap<Point<D>, Visit>>,
+    pub steps: usize,
+}
+
+fn rotate<const D: usize>(mut point: Point<D>, direction: &Direction) -> anyhow::Result<Point<D>> {
+    let offset = direction.offset();
+    let axes = D.min(offset.len());
+
+    ensure!(
+        offset[axes..].iter().all(|&c| c == 0),
+        "unable to move {} in {} dimensions",
+        direction,
+        D
+    );
+
+    for (axis, c) in offset[..axes].iter().enumerate() {
+        point[axis] += c;
+    }
+
+    Ok(point)
+}
+
+fn adjust_tail<const D: usize>(
+    head: Point<D>,
+    mut tail: Point<D>,
+    physics: &RopePhysics,
+) -> Point<D> {
+    while physics.distance(head, tail) > physics.slack {
+        tail = physics.step(head, tail);
     }
 
     tail
 }
 
-impl Rope {
+impl<const D: usize> Rope<D> {
     pub fn new(size: usize) -> Self {
-        let knots = (0..size).map(|_| Point::default()).collect();
+        Self::with_physics(size, RopePhysics::default())
+    }
 
-        let mut trail = HashSet::new();
+    pub fn with_physics(size: usize, physics: RopePhysics) -> Self {
+        let knots = vec![Point::default(); size];
+        let trails = vec![HashSet::from([Point::default()]); size];
+        let visits = vec![HashMap::from([(Point::default(), Visit::new(0))]); size];
 
-        if size > 1 {
-            trail.insert(Point::default());
+        Self {
+            physics,
+            knots,
+            trails,
+            visits,
+            steps: 0,
         }
+    }
 
-        Self { knots, trail }
+    pub fn trail(&self) -> Option<&HashSet<Point<D>>> {
+        self.trails.last()
     }
 
-    pub fn drag_step(&mut self, direction: &Direction) {
-        if self.knots.is_empty() {
+    pub fn stats(&self, knot: usize) -> Option<TrailStats<D>> {
+        self.visits.get(knot).map(TrailStats::new)
+    }
+
+    pub fn tail_stats(&self) -> Option<TrailStats<D>> {
+        self.visits.last().map(TrailStats::new)
+    }
+
+    fn place(&mut self, knot: usize, point: Point<D>) {
+        if self.knots[knot] == point {
             return;
         }
 
-        self.knots[0] = rotate(self.knots[0], direction);
+        self.knots[knot] = point;
+        self.trails[knot].insert(point);
 
-        let mut a = self.knots[0];
+        let step = self.steps;
+        self.visits[knot]
+            .entry(point)
+            .and_modify(|visit| visit.count += 1)
+            .or_insert_with(|| Visit::new(step));
+    }
 
-        for b in self.knots.iter_mut().skip(1) {
-            *b = adjust_tail(a, *b);
-            a = *b;
+    pub fn drag_step(&mut self, direction: &Direction) -> anyhow::Result<()> {
+        if self.knots.is_empty() {
+            return Ok(());
         }
-        self.trail.insert(a);
+
+        let head = rotate(self.knots[0], direction)?;
+
+        self.steps += 1;
+        self.place(0, head);
+
+        for i in 1..self.knots.len() {
+            let point = adjust_tail(self.knots[i - 1], self.knots[i], &self.physics);
+            self.place(i, point);
+        }
+
+        Ok(())
     }
 
-    pub fn drag(&mut self, movement: &Movement) {
+    pub fn drag(&mut self, movement: &Movement) -> anyhow::Result<()> {
         for _ in 0..movement.magnitude {
-            self.drag_step(&movement.direction);
+            self.drag_step(&movement.direction)?;
         }
+
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::core::{Direction::*, Point2, Point3};
+
     use super::*;
 
-    fn assert_tail_after(expected_tail: Point, path: &str) {
+    fn assert_tail_after(expected_tail: Point2, path: &str) {
         let directions = path
             .chars()
             .map(Direction::try_from)
             .collect::<Result<Vec<_>, _>>()
             .unwrap();
 
-        let tail = Point::default();
+        let tail = Point2::default();
         let mut head = tail;
 
         for direction in directions.iter() {
-            head = rotate(head, direction);
+            head = rotate(head, direction).unwrap();
         }
 
-        assert_eq!(adjust_tail(head, tail), expected_tail);
+        assert_eq!(
+            adjust_tail(head, tail, &RopePhysics::default()),
+            expected_tail
+        );
     }
 
     macro_rules! assert_tail {
@@ -135,4 +236,95 @@ mod tests {
         assert_tail!([-1, 1], "ULU", "LUL", "LUU", "ULL");
         assert_tail!([1, 1], "URU", "RUR", "RUU", "URR")
     }
+
+    #[test]
+    fn test_slack() -> anyhow::Result<()> {
+        let physics = RopePhysics {
+            slack: 3,
+            ..Default::default()
+        };
+
+        let mut rope = Rope::<2>::with_physics(2, physics);
+        rope.drag(&Movement::new(Right, 3))?;
+        assert_eq!(rope.knots[1], Point2::new(0, 0));
+
+        rope.drag(&Movement::new(UpRight, 2))?;
+        assert_eq!(rope.knots, [Point2::new(5, 2), Point2::new(2, 2)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_four_way_follow() -> anyhow::Result<()> {
+        let physics = RopePhysics {
+            follow: FollowRule::FourWay,
+            ..Default::default()
+        };
+
+        let mut rope = Rope::<2>::with_physics(3, physics);
+        rope.drag(&Movement::new(UpRight, 1))?;
+        assert_eq!(
+            rope.knots,
+            [Point2::new(1, 1), Point2::new(1, 0), Point2::new(0, 0)]
+        );
+
+        rope.drag(&Movement::new(Up, 2))?;
+        assert_eq!(
+            rope.knots,
+            [Point2::new(1, 3), Point2::new(1, 2), Point2::new(1, 1)]
+        );
+
+        for (a, b) in rope.knots.iter().zip(rope.knots.iter().skip(1)) {
+            assert_eq!((a - b).abs().sum(), 1);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trails() -> anyhow::Result<()> {
+        let mut rope = Rope::<2>::new(3);
+        rope.drag(&Movement::new(Right, 4))?;
+
+        assert_eq!(rope.trails[0].len(), 5);
+        assert_eq!(rope.trails[1].len(), 4);
+        assert_eq!(rope.trails[2].len(), 3);
+        assert_eq!(rope.trail(), rope.trails.get(2));
+        assert!(Rope::<2>::new(0).trail().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_three_dimensions() -> anyhow::Result<()> {
+        let mut rope = Rope::<3>::new(3);
+
+        rope.drag(&Movement::new(Forward, 3))?;
+        assert_eq!(
+            rope.knots,
+            [
+                Point3::new(0, 0, 3),
+                Point3::new(0, 0, 2),
+                Point3::new(0, 0, 1)
+            ]
+        );
+
+        rope.drag(&Movement::new(UpRight, 1))?;
+        assert_eq!(rope.knots[1], Point3::new(0, 0, 2));
+
+        rope.drag(&Movement::new(Back, 4))?;
+        assert_eq!(rope.knots[0], Point3::new(1, 1, -1));
+        assert_eq!(rope.knots[1], Point3::new(1, 1, 0));
+        assert!(rope.trails[1].contains(&Point3::new(1, 1, 1)));
+        assert_eq!(rope.trail().unwrap().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_three_dimensional_moves_in_two_dimensions() {
+        let mut rope = Rope::<2>::new(2);
+        assert!(rope.drag(&Movement::new(Forward, 1)).is_err());
+        assert_eq!(rope.knots, [Point2::default(); 2]);
+    }
 }
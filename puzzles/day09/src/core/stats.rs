@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use super::Point;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Visit {
+    pub count: usize,
+    pub first_step: usize,
+}
+
+impl Visit {
+    pub fn new(first_step: usize) -> Self {
+        Self {
+            count: 1,
+            first_step,
+        }
+    }
+
+    pub fn revisits(&self) -> usize {
+        self.count - 1
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrailStats<const D: usize = 2> {
+    pub min: Point<D>,
+    pub max: Point<D>,
+    pub visits: HashMap<Point<D>, Visit>,
+}
+
+impl<const D: usize> TrailStats<D> {
+    pub fn new(visits: &HashMap<Point<D>, Visit>) -> Self {
+        let mut points = visits.keys();
+
+        let first = points.next().copied().unwrap_or_default();
+
+        let (min, max) = points.fold((first, first), |(min, max), point| {
+            (min.inf(point), max.sup(point))
+        });
+
+        Self {
+            min,
+            max,
+            visits: visits.clone(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.visits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.visits.is_empty()
+    }
+
+    pub fn extent(&self) -> [usize; D] {
+        std::array::from_fn(|axis| (self.max[axis] - self.min[axis]) as usize + 1)
+    }
+
+    pub fn revisits(&self, point: &Point<D>) -> usize {
+        self.visits.get(point).map_or(0, Visit::revisits)
+    }
+
+    pub fn total_revisits(&self) -> usize {
+        self.visits.values().map(Visit::revisits).sum()
+    }
+
+    pub fn first_step(&self, point: &Point<D>) -> Option<usize> {
+        self.visits.get(point).map(|visit| visit.first_step)
+    }
+
+    pub fn most_visited(&self) -> Option<(Point<D>, Visit)> {
+        self.visits
+            .iter()
+            .max_by_key(|(_, visit)| (visit.count, std::cmp::Reverse(visit.first_step)))
+            .map(|(point, visit)| (*point, *visit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use crate::answer::parse1;
+    use crate::core::{Point2, Rope};
+
+    use super::*;
+
+    const INPUT: Input = include_str!("../../input-test-1");
+
+    #[test]
+    fn test_stats() -> anyhow::Result<()> {
+        let mut rope = Rope::<2>::new(2);
+
+        for movement in parse1(INPUT)?.iter() {
+            rope.drag(movement)?;
+        }
+
+        let stats = rope.tail_stats().unwrap();
+
+        assert_eq!(stats.len(), 13);
+        assert_eq!(stats.min, Point2::new(0, 0));
+        assert_eq!(stats.max, Point2::new(4, 4));
+        assert_eq!(stats.extent(), [5, 5]);
+
+        assert_eq!(stats.first_step(&Point2::new(0, 0)), Some(0));
+        assert_eq!(stats.first_step(&Point2::new(1, 0)), Some(2));
+        assert_eq!(stats.first_step(&Point2::new(9, 9)), None);
+
+        assert_eq!(stats.revisits(&Point2::new(0, 0)), 0);
+        assert_eq!(stats.revisits(&Point2::new(4, 3)), 1);
+        assert_eq!(stats.first_step(&Point2::new(4, 3)), Some(8));
+        assert_eq!(stats.total_revisits(), 1);
+        assert_eq!(
+            stats.most_visited(),
+            Some((
+                Point2::new(4, 3),
+                Visit {
+                    count: 2,
+                    first_step: 8
+                }
+            ))
+        );
+
+        Ok(())
+    }
+}
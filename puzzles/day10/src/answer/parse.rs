@@ -1,24 +1,13 @@
-use anyhow::Context;
-
 use aoc::Input;
 
-use crate::core::{Command, Program};
+use crate::core::Program;
 
 type Parsed = Program;
 pub type Parsed1 = Parsed;
 pub type Parsed2 = Parsed;
 
 fn parse(input: Input) -> anyhow::Result<Parsed> {
-    fn parse_command(s: &str) -> anyhow::Result<Command> {
-        s.try_into()
-            .with_context(|| format!("invalid command: {s:?}"))
-    }
-
-    input
-        .lines()
-        .enumerate()
-        .map(|(i, s)| parse_command(s).with_context(|| format!("line number {}", i + 1)))
-        .collect::<Result<Vec<_>, _>>()
+    Program::try_from(input)
 }
 
 pub fn parse1(input: Input) -> anyhow::Result<Parsed1> {
@@ -1,4 +1,4 @@
-use crate::core::Machine;
+use crate::core::{is_lit, Machine, CRT_WIDTH};
 
 use super::{Parsed1, Parsed2};
 
@@ -25,23 +25,14 @@ pub fn solve2(program: &Parsed2) -> anyhow::Result<Solution2> {
     let mut display = String::new();
     let mut machine = Machine::new(program.clone());
 
-    let width = 40;
-
     for (i, register) in machine.run().enumerate() {
-        let position = i % width;
-
-        let mut lit = false;
-
-        if register + 1 >= 0 {
-            let start = (register - 1).max(0) as usize;
-            let end = (register + 1) as usize;
-
-            lit = start <= position && position <= end;
-        }
+        display += if is_lit(i % CRT_WIDTH, register) {
+            "#"
+        } else {
+            "."
+        };
 
-        display += if lit { "#" } else { "." };
-
-        if (i + 1) % width == 0 {
+        if (i + 1) % CRT_WIDTH == 0 {
             display += "\n";
         }
     }
@@ -54,6 +45,7 @@ pub mod tests {
     use aoc::Input;
 
     use crate::answer::{parse1, parse2};
+    use crate::core::recognize;
 
     use super::*;
 
@@ -71,4 +63,11 @@ pub mod tests {
         assert_eq!(solve2(&parse2(INPUT)?)?, OUTPUT2);
         Ok(())
     }
+
+    #[test]
+    fn test_solve2_letters() -> anyhow::Result<()> {
+        let display = solve2(&parse2(include_str!("../../input"))?)?;
+        assert_eq!(recognize(&display)?, "RGZEHURK");
+        Ok(())
+    }
 }
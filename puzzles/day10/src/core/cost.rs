@@ -0,0 +1,47 @@
+use anyhow::ensure;
+
+use super::Opcode;
+
+const fn default_cycles(opcode: Opcode) -> usize {
+    match opcode {
+        Opcode::AddX | Opcode::Add | Opcode::Sub | Opcode::Mul => 2,
+        Opcode::Noop | Opcode::Mov | Opcode::Jmp | Opcode::Jnz => 1,
+    }
+}
+
+// Costs are indexed by opcode, in declaration order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleCosts([usize; Opcode::COUNT]);
+
+impl CycleCosts {
+    // Building the table fails to compile if an opcode is missing from the list or out of order.
+    pub const DEFAULT: Self = {
+        let mut costs = [0; Opcode::COUNT];
+        let mut i = 0;
+
+        while i < Opcode::COUNT {
+            let opcode = Opcode::ALL[i];
+            assert!(opcode as usize == i, "opcodes must be listed in order");
+            costs[i] = default_cycles(opcode);
+            i += 1;
+        }
+
+        Self(costs)
+    };
+
+    pub const fn get(&self, opcode: Opcode) -> usize {
+        self.0[opcode as usize]
+    }
+
+    pub fn set(&mut self, opcode: Opcode, cycles: usize) -> anyhow::Result<()> {
+        ensure!(cycles > 0, "{} must take at least one cycle", opcode);
+        self.0[opcode as usize] = cycles;
+        Ok(())
+    }
+}
+
+impl Default for CycleCosts {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
@@ -0,0 +1,27 @@
+use super::Register;
+
+pub const CRT_WIDTH: usize = 40;
+pub const CRT_HEIGHT: usize = 6;
+
+pub fn is_lit(position: usize, register: Register) -> bool {
+    (register - 1..=register + 1).contains(&(position as Register))
+}
+
+pub fn sprite(register: Register) -> String {
+    (0..CRT_WIDTH)
+        .map(|position| if is_lit(position, register) { '#' } else { '.' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sprite() {
+        assert_eq!(sprite(1), format!("###{}", ".".repeat(37)));
+        assert_eq!(sprite(-1), format!("#{}", ".".repeat(39)));
+        assert_eq!(sprite(-2), ".".repeat(40));
+        assert_eq!(sprite(40), format!("{}#", ".".repeat(39)));
+    }
+}
@@ -0,0 +1,392 @@
+use std::fmt::Write;
+
+use anyhow::ensure;
+
+use super::{is_lit, sprite, Machine, Register, RegisterName, TraceEntry, CRT_WIDTH, X};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    pub fn apply(&self, a: Register, b: Register) -> bool {
+        match self {
+            Self::Equal => a == b,
+            Self::NotEqual => a != b,
+            Self::Less => a < b,
+            Self::LessOrEqual => a <= b,
+            Self::Greater => a > b,
+            Self::GreaterOrEqual => a >= b,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Breakpoint {
+    Cycle(usize),
+    Instruction(usize),
+    Register {
+        name: RegisterName,
+        comparison: Comparison,
+        value: Register,
+    },
+}
+
+impl Breakpoint {
+    // Breakpoints are checked before a cycle begins, so a cycle breakpoint refers to the cycle that
+    // is about to run and an instruction breakpoint to an instruction that is about to start.
+    fn is_hit(&self, machine: &Machine) -> bool {
+        match self {
+            Self::Cycle(cycle) => machine.cycle() + 1 == *cycle,
+            Self::Instruction(index) => {
+                machine.is_starting() && machine.executing().is_some_and(|(i, _)| i == *index)
+            }
+            Self::Register {
+                name,
+                comparison,
+                value,
+            } => comparison.apply(machine.register(name), *value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Breakpoint(usize),
+    CycleLimit,
+    Halted,
+}
+
+// Programs can loop forever, so narration and the debugger give up after a number of cycles.
+pub const MAX_CYCLES: usize = 10_000;
+
+#[derive(Debug, Clone)]
+pub struct Debugger {
+    machine: Machine,
+    breakpoints: Vec<Breakpoint>,
+    trace: Vec<TraceEntry>,
+    paused: bool,
+    max_cycles: usize,
+}
+
+impl Debugger {
+    pub fn new(machine: Machine) -> Self {
+        Self::with_max_cycles(machine, MAX_CYCLES)
+    }
+
+    pub fn with_max_cycles(machine: Machine, max_cycles: usize) -> Self {
+        Self {
+            machine,
+            breakpoints: Vec::new(),
+            trace: Vec::new(),
+            paused: false,
+            max_cycles,
+        }
+    }
+
+    pub fn machine(&self) -> &Machine {
+        &self.machine
+    }
+
+    pub fn trace(&self) -> &[TraceEntry] {
+        &self.trace
+    }
+
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> usize {
+        self.breakpoints.push(breakpoint);
+        self.breakpoints.len() - 1
+    }
+
+    pub fn remove_breakpoint(&mut self, index: usize) -> Option<Breakpoint> {
+        (index < self.breakpoints.len()).then(|| self.breakpoints.remove(index))
+    }
+
+    pub fn step(&mut self) -> Option<&TraceEntry> {
+        self.paused = false;
+        let entry = self.machine.step()?;
+        self.trace.push(entry);
+        self.trace.last()
+    }
+
+    pub fn step_instruction(&mut self) -> Option<&TraceEntry> {
+        loop {
+            let finished = self.step()?.cycles_remaining == 0;
+            if finished {
+                return self.trace.last();
+            }
+        }
+    }
+
+    pub fn resume(&mut self) -> Stop {
+        loop {
+            if self.machine.is_halted() {
+                return Stop::Halted;
+            }
+
+            if self.machine.cycle() >= self.max_cycles {
+                return Stop::CycleLimit;
+            }
+
+            if !self.paused {
+                if let Some(i) = self
+                    .breakpoints
+                    .iter()
+                    .position(|breakpoint| breakpoint.is_hit(&self.machine))
+                {
+                    self.paused = true;
+                    return Stop::Breakpoint(i);
+                }
+            }
+
+            self.step();
+        }
+    }
+}
+
+pub fn narrate(machine: Machine, max_cycles: usize) -> anyhow::Result<String> {
+    let mut machine = machine;
+    let mut s = String::new();
+    let mut row = String::new();
+
+    writeln!(s, "Sprite position: {}", sprite(machine.register(X))).unwrap();
+
+    loop {
+        let starting = machine.is_starting();
+
+        let entry = match machine.step() {
+            Some(entry) => entry,
+            None => break,
+        };
+
+        ensure!(
+            entry.cycle <= max_cycles,
+            "program did not halt within {} cycles",
+            max_cycles
+        );
+
+        let position = (entry.cycle - 1) % CRT_WIDTH;
+
+        if position == 0 {
+            row.clear();
+        }
+
+        row.push(if is_lit(position, entry.register) {
+            '#'
+        } else {
+            '.'
+        });
+
+        writeln!(s).unwrap();
+
+        if starting {
+            writeln!(
+                s,
+                "Start cycle {:>3}: begin executing {}",
+                entry.cycle, entry.command
+            )
+            .unwrap();
+        }
+
+        writeln!(
+            s,
+            "During cycle {:>2}: CRT draws pixel in position {}",
+            entry.cycle, position
+        )
+        .unwrap();
+        writeln!(s, "Current CRT row: {}", row).unwrap();
+
+        if entry.cycles_remaining == 0 {
+            write!(
+                s,
+                "End of cycle {:>2}: finish executing {}",
+                entry.cycle, entry.command
+            )
+            .unwrap();
+
+            match entry.command.destination() {
+                Some(name) => {
+                    writeln!(
+                        s,
+                        " (Register {} is now {})",
+                        name.to_uppercase(),
+                        machine.register(name)
+                    )
+                    .unwrap();
+
+                    if name == X {
+                        writeln!(s, "Sprite position: {}", sprite(machine.register(name))).unwrap();
+                    }
+                }
+                None => writeln!(s).unwrap(),
+            }
+        }
+    }
+
+    Ok(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use crate::core::{Command, Program};
+
+    use super::*;
+
+    const INPUT: Input = include_str!("../../input-test");
+
+    fn debugger(input: &str) -> Debugger {
+        Debugger::new(Machine::new(Program::try_from(input).unwrap()))
+    }
+
+    #[test]
+    fn test_cycle_breakpoint() {
+        let mut debugger = debugger(INPUT);
+        debugger.add_breakpoint(Breakpoint::Cycle(20));
+        debugger.add_breakpoint(Breakpoint::Cycle(1));
+
+        assert_eq!(debugger.resume(), Stop::Breakpoint(1));
+        assert!(debugger.trace().is_empty());
+
+        assert_eq!(debugger.resume(), Stop::Breakpoint(0));
+        assert_eq!(debugger.trace().len(), 19);
+
+        let entry = debugger.step().unwrap();
+        assert_eq!(entry.cycle, 20);
+        assert_eq!(entry.register, 21);
+
+        assert_eq!(debugger.resume(), Stop::Halted);
+        assert_eq!(debugger.trace().len(), 240);
+    }
+
+    #[test]
+    fn test_instruction_breakpoint() {
+        let mut debugger = debugger(INPUT);
+        debugger.add_breakpoint(Breakpoint::Instruction(2));
+
+        assert_eq!(debugger.resume(), Stop::Breakpoint(0));
+        assert_eq!(debugger.machine().cycle(), 4);
+        assert_eq!(debugger.machine().register(X), 5);
+
+        assert_eq!(
+            debugger.remove_breakpoint(0),
+            Some(Breakpoint::Instruction(2))
+        );
+        assert_eq!(debugger.remove_breakpoint(0), None);
+    }
+
+    #[test]
+    fn test_register_breakpoint() {
+        let mut debugger = debugger(INPUT);
+        debugger.add_breakpoint(Breakpoint::Register {
+            name: X.into(),
+            comparison: Comparison::Less,
+            value: 0,
+        });
+
+        assert_eq!(debugger.resume(), Stop::Breakpoint(0));
+        assert!(debugger.machine().register(X) < 0);
+        assert!(debugger.trace().iter().all(|entry| entry.register >= 0));
+    }
+
+    #[test]
+    fn test_cycle_limit() {
+        let program = Program::try_from("start:\naddx 1\njmp start").unwrap();
+        let mut debugger = Debugger::with_max_cycles(Machine::new(program), 100);
+
+        assert_eq!(debugger.resume(), Stop::CycleLimit);
+        assert_eq!(debugger.trace().len(), 100);
+        assert_eq!(debugger.resume(), Stop::CycleLimit);
+        assert_eq!(debugger.trace().len(), 100);
+
+        let program = Program::try_from(INPUT).unwrap();
+        let mut debugger = Debugger::with_max_cycles(Machine::new(program), 240);
+        assert_eq!(debugger.resume(), Stop::Halted);
+    }
+
+    #[test]
+    fn test_step_instruction() {
+        let mut debugger = debugger("noop\naddx 3\naddx -5");
+
+        let entry = debugger.step_instruction().unwrap();
+        assert_eq!((entry.cycle, entry.instruction), (1, 0));
+
+        let entry = debugger.step_instruction().unwrap().clone();
+        assert_eq!(entry.cycle, 3);
+        assert_eq!(entry.command, Command::AddX(3));
+        assert_eq!(debugger.machine().register(X), 4);
+
+        assert_eq!(
+            debugger.trace()[1],
+            TraceEntry {
+                cycle: 2,
+                instruction: 1,
+                command: Command::AddX(3),
+                cycles_remaining: 1,
+                register: 1,
+            }
+        );
+
+        debugger.step_instruction();
+        assert!(debugger.step_instruction().is_none());
+    }
+
+    #[test]
+    fn test_narrate() -> anyhow::Result<()> {
+        let narration = narrate(Machine::new(Program::try_from(INPUT)?), 240)?;
+
+        assert!(narration.starts_with(
+            "\
+Sprite position: ###.....................................
+
+Start cycle   1: begin executing addx 15
+During cycle  1: CRT draws pixel in position 0
+Current CRT row: #
+
+During cycle  2: CRT draws pixel in position 1
+Current CRT row: ##
+End of cycle  2: finish executing addx 15 (Register X is now 16)
+Sprite position: ...............###......................
+
+Start cycle   3: begin executing addx -11
+"
+        ));
+
+        assert!(narration.contains(
+            "\
+End of cycle 18: finish executing addx 4 (Register X is now 21)
+Sprite position: ....................###.................
+
+Start cycle  19: begin executing noop
+During cycle 19: CRT draws pixel in position 18
+Current CRT row: ##..##..##..##..##.
+End of cycle 19: finish executing noop
+
+Start cycle  20: begin executing addx -1
+"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_narrate_limit() -> anyhow::Result<()> {
+        let program = Program::try_from("start:\naddx 1\njmp start")?;
+        let error = narrate(Machine::new(program), 100).unwrap_err();
+        assert_eq!(error.to_string(), "program did not halt within 100 cycles");
+
+        assert!(narrate(Machine::new(Program::try_from(INPUT)?), 239).is_err());
+
+        Ok(())
+    }
+}
@@ -1,22 +1,41 @@
-use crate::core::{Command, Program};
+use std::collections::HashMap;
+
+use crate::core::{Command, CycleCosts, Operand, Program, RegisterName, X};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub cycle: usize,
+    pub instruction: usize,
+    pub command: Command,
+    pub cycles_remaining: usize,
+    pub register: Register,
+}
 
 pub type Register = isize;
 
 #[derive(Debug, Clone)]
 pub struct Machine {
     program: Program,
-    register: Register,
-    executing: Option<Command>,
+    costs: CycleCosts,
+    registers: HashMap<RegisterName, Register>,
+    cycle: usize,
+    counter: usize,
+    executing: Option<usize>,
     cycles_remaining: usize,
 }
 
 impl Machine {
-    pub fn new(mut program: Program) -> Self {
-        program.reverse();
+    pub fn new(program: Program) -> Self {
+        Self::with_costs(program, CycleCosts::default())
+    }
 
+    pub fn with_costs(program: Program, costs: CycleCosts) -> Self {
         let mut machine = Self {
             program,
-            register: 1,
+            costs,
+            registers: HashMap::from([(X.to_string(), 1)]),
+            cycle: 0,
+            counter: 0,
             executing: None,
             cycles_remaining: 0,
         };
@@ -26,41 +45,195 @@ impl Machine {
         machine
     }
 
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    pub fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    pub fn executing(&self) -> Option<(usize, &Command)> {
+        self.executing
+            .map(|index| (index, &self.program.commands()[index]))
+    }
+
+    pub fn cycles_remaining(&self) -> usize {
+        self.cycles_remaining
+    }
+
+    pub fn is_starting(&self) -> bool {
+        self.executing()
+            .is_some_and(|(_, command)| self.cycles_remaining == self.costs.get(command.opcode()))
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.executing.is_none()
+    }
+
+    pub fn register(&self, name: &str) -> Register {
+        self.registers.get(name).copied().unwrap_or_default()
+    }
+
+    pub fn set_register(&mut self, name: &str, value: Register) {
+        self.registers.insert(name.to_string(), value);
+    }
+
+    fn value(&self, operand: &Operand) -> Register {
+        match operand {
+            Operand::Register(name) => self.register(name),
+            Operand::Value(value) => *value,
+        }
+    }
+
+    fn update(&mut self, name: &str, f: impl FnOnce(Register) -> Register) {
+        let value = f(self.register(name));
+        self.set_register(name, value);
+    }
+
     fn next_command(&mut self) {
-        if let Some(command) = self.program.pop() {
-            self.executing = Some(command);
-            self.cycles_remaining = command.cycles();
+        if let Some(command) = self.program.get(self.counter) {
+            self.executing = Some(self.counter);
+            self.cycles_remaining = self.costs.get(command.opcode());
+            self.counter += 1;
         } else {
             self.executing = None;
             self.cycles_remaining = 0;
         }
     }
 
-    fn tick(&mut self) -> Option<Register> {
-        if let Some(command) = self.executing {
-            self.cycles_remaining -= 1;
-
-            let register = self.register;
-            let finished = self.cycles_remaining == 0;
-
-            if finished {
-                match command {
-                    Command::Noop => {}
-                    Command::AddX(value) => {
-                        self.register += value;
-                    }
-                }
+    fn jump(&mut self, label: &str) {
+        self.counter = self
+            .program
+            .target(label)
+            .expect("program labels are validated");
+    }
 
-                self.next_command();
+    fn execute(&mut self, command: &Command) {
+        match command {
+            Command::Noop => {}
+            Command::AddX(value) => {
+                self.update(X, |x| x.wrapping_add(*value));
+            }
+            Command::Add(name, operand) => {
+                let value = self.value(operand);
+                self.update(name, |x| x.wrapping_add(value));
+            }
+            Command::Sub(name, operand) => {
+                let value = self.value(operand);
+                self.update(name, |x| x.wrapping_sub(value));
+            }
+            Command::Mul(name, operand) => {
+                let value = self.value(operand);
+                self.update(name, |x| x.wrapping_mul(value));
+            }
+            Command::Mov(name, operand) => {
+                let value = self.value(operand);
+                self.set_register(name, value);
             }
+            Command::Jmp(label) => {
+                self.jump(label);
+            }
+            Command::Jnz(operand, label) => {
+                if self.value(operand) != 0 {
+                    self.jump(label);
+                }
+            }
+        }
+    }
 
-            Some(register)
-        } else {
-            None
+    pub fn step(&mut self) -> Option<TraceEntry> {
+        let index = self.executing?;
+        let command = self.program.commands()[index].clone();
+
+        self.cycle += 1;
+        self.cycles_remaining -= 1;
+
+        let entry = TraceEntry {
+            cycle: self.cycle,
+            instruction: index,
+            command,
+            cycles_remaining: self.cycles_remaining,
+            register: self.register(X),
+        };
+
+        if self.cycles_remaining == 0 {
+            self.execute(&entry.command);
+            self.next_command();
         }
+
+        Some(entry)
+    }
+
+    fn tick(&mut self) -> Option<Register> {
+        self.step().map(|entry| entry.register)
     }
 
     pub fn run(&mut self) -> impl Iterator<Item = Register> + '_ {
         std::iter::from_fn(|| self.tick())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::core::Opcode;
+
+    use super::*;
+
+    fn run(program: &str, costs: CycleCosts) -> anyhow::Result<(Vec<Register>, Machine)> {
+        let mut machine = Machine::with_costs(Program::try_from(program)?, costs);
+        let values = machine.run().take(1000).collect();
+        Ok((values, machine))
+    }
+
+    #[test]
+    fn test_original() -> anyhow::Result<()> {
+        let (values, _) = run("noop\naddx 3\naddx -5", CycleCosts::default())?;
+        assert_eq!(values, [1, 1, 1, 4, 4]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_loop() -> anyhow::Result<()> {
+        let program = "\
+mov n 3
+loop:
+addx 2
+sub n 1
+jnz n loop
+mul x x
+";
+        let (values, machine) = run(program, CycleCosts::default())?;
+
+        assert_eq!(machine.register(X), 49);
+        assert_eq!(machine.register("n"), 0);
+        assert_eq!(values.len(), 1 + 3 * (2 + 2 + 1) + 2);
+        assert_eq!(values[..6], [1, 1, 1, 3, 3, 3]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_costs() -> anyhow::Result<()> {
+        let mut costs = CycleCosts::default();
+        costs.set(Opcode::AddX, 3)?;
+        costs.set(Opcode::Noop, 2)?;
+        assert!(costs.set(Opcode::Jmp, 0).is_err());
+        assert_eq!(costs.get(Opcode::AddX), 3);
+        assert_eq!(costs.get(Opcode::Mul), 2);
+        assert_eq!(CycleCosts::DEFAULT.get(Opcode::Jnz), 1);
+
+        let (values, _) = run("noop\naddx 3", costs)?;
+        assert_eq!(values, [1, 1, 1, 1, 1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jmp() -> anyhow::Result<()> {
+        let (values, machine) = run("jmp skip\naddx 100\nskip:\naddx 1", CycleCosts::default())?;
+        assert_eq!(values, [1, 1, 1]);
+        assert_eq!(machine.register(X), 2);
+        Ok(())
+    }
+}
@@ -1,5 +1,20 @@
+pub mod cost;
+pub use cost::*;
+
 pub mod machine;
 pub use machine::*;
 
 pub mod program;
 pub use program::*;
+
+pub mod ocr;
+pub use ocr::*;
+
+pub mod crt;
+pub use crt::*;
+
+pub mod debugger;
+pub use debugger::*;
+
+pub mod synthesis;
+pub use synthesis::*;
@@ -0,0 +1,154 @@
+use anyhow::{anyhow, ensure};
+
+pub const GLYPH_WIDTH: usize = 4;
+pub const GLYPH_HEIGHT: usize = 6;
+pub const GLYPH_STRIDE: usize = GLYPH_WIDTH + 1;
+
+pub type Glyph = [&'static str; GLYPH_HEIGHT];
+
+pub const FONT: [(char, Glyph); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+    (' ', ["....", "....", "....", "....", "....", "...."]),
+];
+
+pub fn glyph(letter: char) -> Option<&'static Glyph> {
+    FONT.iter()
+        .find(|(c, _)| *c == letter)
+        .map(|(_, glyph)| glyph)
+}
+
+fn letter(rows: &[&str], column: usize) -> Option<char> {
+    FONT.iter()
+        .find(|(_, glyph)| {
+            glyph
+                .iter()
+                .zip(rows.iter())
+                .all(|(expected, row)| row.get(column..column + GLYPH_WIDTH) == Some(*expected))
+        })
+        .map(|(c, _)| *c)
+}
+
+pub fn render_text(text: &str, width: usize) -> anyhow::Result<String> {
+    let glyphs = text
+        .chars()
+        .map(|c| glyph(c).ok_or_else(|| anyhow!("no glyph for character: {:?}", c)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let used = (glyphs.len() * GLYPH_STRIDE).saturating_sub(1);
+
+    ensure!(
+        used <= width,
+        "{:?} needs {} columns, but the display is {} wide",
+        text,
+        used,
+        width
+    );
+
+    Ok((0..GLYPH_HEIGHT)
+        .map(|row| {
+            let line: Vec<&str> = glyphs.iter().map(|glyph| glyph[row]).collect();
+            format!("{:.<width$}\n", line.join("."), width = width)
+        })
+        .collect())
+}
+
+pub fn recognize(display: &str) -> anyhow::Result<String> {
+    let rows: Vec<&str> = display.lines().collect();
+
+    ensure!(
+        rows.len() == GLYPH_HEIGHT,
+        "expected display to have {} rows, but it had {}",
+        GLYPH_HEIGHT,
+        rows.len()
+    );
+
+    let width = rows[0].len();
+
+    ensure!(
+        rows.iter().all(|row| row.len() == width),
+        "display rows have different widths"
+    );
+    ensure!(
+        rows.iter()
+            .all(|row| row.chars().all(|c| c == '#' || c == '.')),
+        "display may only contain '#' and '.'"
+    );
+
+    let mut letters = String::new();
+    let mut unrecognized = Vec::new();
+
+    for column in (0..width).step_by(GLYPH_STRIDE) {
+        match letter(&rows, column) {
+            Some(c) => letters.push(c),
+            None => unrecognized.push(format!(
+                "{}-{}",
+                column + 1,
+                (column + GLYPH_WIDTH).min(width)
+            )),
+        }
+    }
+
+    if !unrecognized.is_empty() {
+        return Err(anyhow!(
+            "unrecognized glyphs at columns {}",
+            unrecognized.join(", ")
+        ));
+    }
+
+    Ok(letters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(text: &str) -> String {
+        render_text(text, text.len() * GLYPH_STRIDE - 1).unwrap()
+    }
+
+    #[test]
+    fn test_recognize() -> anyhow::Result<()> {
+        let text: String = FONT.iter().map(|(c, _)| *c).collect();
+        assert_eq!(recognize(&render(&text))?, text);
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_text() -> anyhow::Result<()> {
+        assert_eq!(
+            render_text("LI", 10)?,
+            "#.....###.\n#......#..\n#......#..\n#......#..\n#......#..\n####..###.\n"
+        );
+        assert!(render_text("ABCEFGHIJ", 40).is_err());
+        assert!(render_text("a", 40).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_unrecognized() {
+        let mut display = render("AB");
+        display.replace_range(0..1, "#");
+
+        let error = recognize(&display).unwrap_err();
+        assert_eq!(error.to_string(), "unrecognized glyphs at columns 1-4");
+
+        assert!(recognize("#..#\n").is_err());
+        assert!(recognize(&render("A").replace('#', "x")).is_err());
+    }
+}
@@ -1,20 +1,171 @@
+use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::fmt;
 
-use anyhow::{anyhow, Context};
+use anyhow::{anyhow, ensure, Context};
 
-pub type Program = Vec<Command>;
+pub type RegisterName = String;
+pub type Label = String;
 
-#[derive(Debug, Clone, Copy)]
+pub const X: &str = "x";
+
+fn is_identifier(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn parse_identifier(s: &str) -> anyhow::Result<String> {
+    ensure!(is_identifier(s), "invalid identifier: {:?}", s);
+    Ok(s.to_string())
+}
+
+fn parse_value(s: &str) -> anyhow::Result<isize> {
+    s.parse()
+        .with_context(|| format!("invalid integer: {:?}", s))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+    Register(RegisterName),
+    Value(isize),
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Register(name) => write!(f, "{}", name),
+            Self::Value(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl TryFrom<&str> for Operand {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        if is_identifier(s) {
+            Ok(Self::Register(s.to_string()))
+        } else {
+            parse_value(s).map(Self::Value)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Opcode {
+    Noop,
+    AddX,
+    Add,
+    Sub,
+    Mul,
+    Mov,
+    Jmp,
+    Jnz,
+}
+
+impl Opcode {
+    // Every opcode, in declaration order, so that each one is also its own index.
+    pub const ALL: [Self; 8] = [
+        Self::Noop,
+        Self::AddX,
+        Self::Add,
+        Self::Sub,
+        Self::Mul,
+        Self::Mov,
+        Self::Jmp,
+        Self::Jnz,
+    ];
+
+    pub const COUNT: usize = Self::ALL.len();
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Noop => "noop",
+            Self::AddX => "addx",
+            Self::Add => "add",
+            Self::Sub => "sub",
+            Self::Mul => "mul",
+            Self::Mov => "mov",
+            Self::Jmp => "jmp",
+            Self::Jnz => "jnz",
+        }
+    }
+}
+
+impl fmt::Display for Opcode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl TryFrom<&str> for Opcode {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::ALL
+            .into_iter()
+            .find(|opcode| opcode.name() == s)
+            .ok_or_else(|| anyhow!("unrecognized command: {:?}", s))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Command {
     Noop,
     AddX(isize),
+    Add(RegisterName, Operand),
+    Sub(RegisterName, Operand),
+    Mul(RegisterName, Operand),
+    Mov(RegisterName, Operand),
+    Jmp(Label),
+    Jnz(Operand, Label),
 }
 
 impl Command {
-    pub fn cycles(&self) -> usize {
+    pub fn opcode(&self) -> Opcode {
+        match self {
+            Self::Noop => Opcode::Noop,
+            Self::AddX(_) => Opcode::AddX,
+            Self::Add(..) => Opcode::Add,
+            Self::Sub(..) => Opcode::Sub,
+            Self::Mul(..) => Opcode::Mul,
+            Self::Mov(..) => Opcode::Mov,
+            Self::Jmp(_) => Opcode::Jmp,
+            Self::Jnz(..) => Opcode::Jnz,
+        }
+    }
+
+    pub fn destination(&self) -> Option<&str> {
         match self {
-            Self::Noop => 1,
-            Self::AddX(_) => 2,
+            Self::AddX(_) => Some(X),
+            Self::Add(name, _) | Self::Sub(name, _) | Self::Mul(name, _) | Self::Mov(name, _) => {
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        match self {
+            Self::Jmp(label) | Self::Jnz(_, label) => Some(label),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let opcode = self.opcode();
+
+        match self {
+            Self::Noop => write!(f, "{}", opcode),
+            Self::AddX(value) => write!(f, "{} {}", opcode, value),
+            Self::Add(register, operand)
+            | Self::Sub(register, operand)
+            | Self::Mul(register, operand)
+            | Self::Mov(register, operand) => write!(f, "{} {} {}", opcode, register, operand),
+            Self::Jmp(label) => write!(f, "{} {}", opcode, label),
+            Self::Jnz(operand, label) => write!(f, "{} {} {}", opcode, operand, label),
         }
     }
 }
@@ -25,20 +176,212 @@ impl TryFrom<&str> for Command {
     fn try_from(s: &str) -> Result<Self, Self::Error> {
         let mut words = s.split_whitespace();
 
-        let command = words.next().context("missing command name")?;
-
-        match command {
-            "noop" => Ok(Self::Noop),
-            "addx" => {
-                let arg = words
-                    .next()
-                    .with_context(|| format!("{:?} command is missing it's argument", command))?;
-                let value: isize = arg
-                    .parse()
-                    .with_context(|| format!("invalid integer: {:?}", arg))?;
-                Ok(Self::AddX(value))
+        let name = words.next().context("missing command name")?;
+        let opcode = Opcode::try_from(name)?;
+
+        let mut arg = |what: &str| {
+            words
+                .next()
+                .with_context(|| format!("{:?} command is missing its {}", name, what))
+        };
+
+        let command = match opcode {
+            Opcode::Noop => Self::Noop,
+            Opcode::AddX => Self::AddX(parse_value(arg("argument")?)?),
+            Opcode::Add | Opcode::Sub | Opcode::Mul | Opcode::Mov => {
+                let register = parse_identifier(arg("register")?)?;
+                let operand = Operand::try_from(arg("operand")?)?;
+
+                match opcode {
+                    Opcode::Add => Self::Add(register, operand),
+                    Opcode::Sub => Self::Sub(register, operand),
+                    Opcode::Mul => Self::Mul(register, operand),
+                    _ => Self::Mov(register, operand),
+                }
+            }
+            Opcode::Jmp => Self::Jmp(parse_identifier(arg("label")?)?),
+            Opcode::Jnz => {
+                let operand = Operand::try_from(arg("operand")?)?;
+                Self::Jnz(operand, parse_identifier(arg("label")?)?)
             }
-            _ => Err(anyhow!("unrecognized command: {:?}", command)),
+        };
+
+        if let Some(extra) = words.next() {
+            return Err(anyhow!("unexpected argument: {:?}", extra));
         }
+
+        Ok(command)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Program {
+    commands: Vec<Command>,
+    labels: HashMap<Label, usize>,
+}
+
+impl Program {
+    pub fn new(commands: Vec<Command>, labels: HashMap<Label, usize>) -> anyhow::Result<Self> {
+        for (label, &index) in labels.iter() {
+            ensure!(
+                index <= commands.len(),
+                "label {:?} points past the end of the program",
+                label
+            );
+        }
+
+        for (i, command) in commands.iter().enumerate() {
+            if let Some(label) = command.label() {
+                ensure!(
+                    labels.contains_key(label),
+                    "command number {} jumps to undefined label {:?}",
+                    i + 1,
+                    label
+                );
+            }
+        }
+
+        Ok(Self { commands, labels })
+    }
+
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Command> {
+        self.commands.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn target(&self, label: &str) -> Option<usize> {
+        self.labels.get(label).copied()
+    }
+}
+
+impl TryFrom<Vec<Command>> for Program {
+    type Error = anyhow::Error;
+
+    fn try_from(commands: Vec<Command>) -> Result<Self, Self::Error> {
+        Self::new(commands, HashMap::new())
+    }
+}
+
+impl TryFrom<&str> for Program {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let mut commands = Vec::new();
+        let mut labels = HashMap::new();
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let result = match line.strip_suffix(':') {
+                Some(label) => parse_identifier(label).and_then(|label| {
+                    ensure!(!labels.contains_key(&label), "duplicate label: {:?}", label);
+                    labels.insert(label, commands.len());
+                    Ok(())
+                }),
+                None => Command::try_from(line)
+                    .with_context(|| format!("invalid command: {:?}", line))
+                    .map(|command| commands.push(command)),
+            };
+
+            result.with_context(|| format!("line number {}", i + 1))?;
+        }
+
+        Self::new(commands, labels)
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut labels: Vec<_> = self.labels.iter().collect();
+        labels.sort_unstable_by_key(|&(label, &index)| (index, label));
+
+        let mut labels = labels.into_iter().peekable();
+
+        for i in 0..=self.commands.len() {
+            while let Some((label, _)) = labels.next_if(|&(_, &index)| index == i) {
+                writeln!(f, "{}:", label)?;
+            }
+
+            if let Some(command) = self.commands.get(i) {
+                writeln!(f, "{}", command)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: &str = "\
+mov n 3
+loop:
+addx 2
+sub n 1
+jnz n loop
+mul x x
+end:
+";
+
+    #[test]
+    fn test_parse() -> anyhow::Result<()> {
+        let program = Program::try_from(PROGRAM)?;
+
+        assert_eq!(program.len(), 5);
+        assert_eq!(program.target("loop"), Some(1));
+        assert_eq!(program.target("end"), Some(5));
+        assert_eq!(
+            program.get(3),
+            Some(&Command::Jnz(Operand::Register("n".into()), "loop".into()))
+        );
+        assert_eq!(program.to_string(), PROGRAM);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_original() -> anyhow::Result<()> {
+        let program = Program::try_from("noop\naddx 3\naddx -5\n")?;
+        assert_eq!(
+            program.commands(),
+            [Command::Noop, Command::AddX(3), Command::AddX(-5)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_opcodes() -> anyhow::Result<()> {
+        for (i, opcode) in Opcode::ALL.into_iter().enumerate() {
+            assert_eq!(opcode as usize, i);
+            assert_eq!(Opcode::try_from(opcode.name())?, opcode);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(Program::try_from("jmp nowhere").is_err());
+        assert!(Program::try_from("a:\na:\nnoop").is_err());
+        assert!(Program::try_from("mov 3 x").is_err());
+        assert!(Program::try_from("addx").is_err());
+        assert!(Program::try_from("noop 1").is_err());
+        assert!(Program::try_from("halt").is_err());
     }
 }
@@ -0,0 +1,196 @@
+use anyhow::{anyhow, ensure};
+
+use super::{is_lit, render_text, Command, Program, Register, CRT_HEIGHT, CRT_WIDTH};
+
+pub type Bitmap = [[bool; CRT_WIDTH]; CRT_HEIGHT];
+
+const PIXELS: usize = CRT_WIDTH * CRT_HEIGHT;
+
+// Register values of -2 and below, and of 41 and above, leave a row dark, so they are
+// interchangeable and -2 stands in for all of them. A value of 40 is kept because its sprite still
+// lights the last column.
+const MIN_REGISTER: Register = -2;
+const MAX_REGISTER: Register = CRT_WIDTH as Register;
+
+pub fn parse_bitmap(s: &str) -> anyhow::Result<Bitmap> {
+    let rows: Vec<&str> = s.lines().collect();
+
+    ensure!(
+        rows.len() == CRT_HEIGHT,
+        "expected image to have {} rows, but it had {}",
+        CRT_HEIGHT,
+        rows.len()
+    );
+
+    let mut bitmap = [[false; CRT_WIDTH]; CRT_HEIGHT];
+
+    for (i, row) in rows.iter().enumerate() {
+        ensure!(
+            row.len() == CRT_WIDTH,
+            "expected row number {} to have {} columns, but it had {}",
+            i + 1,
+            CRT_WIDTH,
+            row.len()
+        );
+
+        for (j, c) in row.chars().enumerate() {
+            bitmap[i][j] = match c {
+                '#' => true,
+                '.' => false,
+                _ => return Err(anyhow!("invalid pixel: {:?}", c)),
+            };
+        }
+    }
+
+    Ok(bitmap)
+}
+
+pub fn synthesize(bitmap: &Bitmap) -> anyhow::Result<Program> {
+    let values = (MAX_REGISTER - MIN_REGISTER + 1) as usize;
+    let index = |register: Register| (register - MIN_REGISTER) as usize;
+    let register = |index: usize| index as Register + MIN_REGISTER;
+
+    let draws = |cycle: usize, register: Register| {
+        is_lit(cycle % CRT_WIDTH, register) == bitmap[cycle / CRT_WIDTH][cycle % CRT_WIDTH]
+    };
+
+    // best[cycle][value] is the fewest commands that reach the start of the cycle with that
+    // register value, along with the value and command length it was reached from.
+    let mut best: Vec<Vec<Option<(usize, usize, usize)>>> = vec![vec![None; values]; PIXELS + 1];
+    best[0][index(1)] = Some((0, 0, 0));
+
+    let mut furthest = 0;
+
+    for cycle in 0..PIXELS {
+        for value in 0..values {
+            let Some((count, _, _)) = best[cycle][value] else {
+                continue;
+            };
+
+            furthest = cycle;
+
+            let x = register(value);
+
+            if !draws(cycle, x) {
+                continue;
+            }
+
+            let mut relax = |length: usize, next_value: usize| {
+                let next = &mut best[cycle + length][next_value];
+                if next.is_none_or(|(other, _, _)| count + 1 < other) {
+                    *next = Some((count + 1, value, length));
+                }
+            };
+
+            relax(1, value);
+
+            if cycle + 2 <= PIXELS && draws(cycle + 1, x) {
+                for next_value in 0..values {
+                    relax(2, next_value);
+                }
+            }
+        }
+    }
+
+    let Some(mut value) = (0..values)
+        .filter(|&value| best[PIXELS][value].is_some())
+        .min_by_key(|&value| best[PIXELS][value].map(|(count, _, _)| count))
+    else {
+        return Err(anyhow!(
+            "image is unreachable: the pixel at row {}, column {} cannot be drawn",
+            furthest / CRT_WIDTH + 1,
+            furthest % CRT_WIDTH + 1
+        ));
+    };
+
+    let mut commands = Vec::new();
+    let mut cycle = PIXELS;
+
+    while cycle > 0 {
+        let (_, previous, length) = best[cycle][value].unwrap();
+
+        commands.push(if length == 1 {
+            Command::Noop
+        } else {
+            Command::AddX(register(value) - register(previous))
+        });
+
+        cycle -= length;
+        value = previous;
+    }
+
+    commands.reverse();
+
+    Program::try_from(commands)
+}
+
+pub fn synthesize_text(text: &str) -> anyhow::Result<Program> {
+    synthesize(&parse_bitmap(&render_text(text, CRT_WIDTH)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::Input;
+
+    use crate::answer::{parse2, solve2};
+    use crate::core::{recognize, Machine};
+
+    use super::*;
+
+    fn display(program: &Program) -> String {
+        solve2(program).unwrap()
+    }
+
+    fn assert_draws(image: &str) -> anyhow::Result<()> {
+        let program = synthesize(&parse_bitmap(image)?)?;
+
+        assert!(program
+            .commands()
+            .iter()
+            .all(|command| matches!(command, Command::Noop | Command::AddX(_))));
+        assert_eq!(Machine::new(program.clone()).run().count(), PIXELS);
+        assert_eq!(display(&program), image);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_synthesize_examples() -> anyhow::Result<()> {
+        let inputs: [Input; 2] = [
+            include_str!("../../input-test"),
+            include_str!("../../input"),
+        ];
+
+        for input in inputs {
+            assert_draws(&display(&parse2(input)?))?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_synthesize_text() -> anyhow::Result<()> {
+        for text in ["RKAZAJBR", "ZEBRA", "FLIP", "BE SURE"] {
+            let program = synthesize_text(text)?;
+            assert_eq!(recognize(&display(&program))?.trim_end(), text);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let error = synthesize_text("ACE").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "image is unreachable: the pixel at row 1, column 1 cannot be drawn"
+        );
+
+        let mut image = display(&synthesize_text("ZEBRA").unwrap());
+        image.replace_range(1..2, ".");
+        assert!(synthesize(&parse_bitmap(&image).unwrap()).is_err());
+
+        assert!(parse_bitmap("#").is_err());
+        assert!(synthesize_text("TOO LONG TEXT").is_err());
+    }
+}
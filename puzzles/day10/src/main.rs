@@ -1,10 +1,59 @@
+use std::io::Read;
+
+use anyhow::{ensure, Context};
+
 use aoc::{Input, Parse, Solve};
 
 use day10::aoc::{Parser1, Parser2, Solver1, Solver2};
+use day10::core::{narrate, Machine, Program, MAX_CYCLES};
 
 const INPUT: Input = include_str!("../input");
 
+fn trace(mut args: impl Iterator<Item = String>) -> anyhow::Result<()> {
+    let mut path = None;
+    let mut max_cycles = MAX_CYCLES;
+
+    while let Some(arg) = args.next() {
+        if arg == "--max-cycles" {
+            let value = args.next().context("missing value for --max-cycles")?;
+            max_cycles = value
+                .parse()
+                .with_context(|| format!("invalid cycle limit: {:?}", value))?;
+        } else {
+            ensure!(path.is_none(), "unexpected argument: {:?}", arg);
+            path = Some(arg);
+        }
+    }
+
+    let input = match path {
+        Some(path) => {
+            std::fs::read_to_string(&path).with_context(|| format!("unable to read {:?}", path))?
+        }
+        None => {
+            let mut input = String::new();
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .context("unable to read standard input")?;
+            input
+        }
+    };
+
+    print!(
+        "{}",
+        narrate(Machine::new(Program::try_from(input.as_str())?), max_cycles)?
+    );
+
+    Ok(())
+}
+
 fn main() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+
+    if let Some(mode) = args.next() {
+        ensure!(mode == "trace", "unrecognized mode: {:?}", mode);
+        return trace(args);
+    }
+
     println!(
         "Part 1 solution: {:?}",
         Solver1(Parser1(INPUT).parse()?).solve()?
@@ -1,4 +1,4 @@
-use crate::core::{Item, MonkeyTroop};
+use crate::core::{MonkeyTroop, WorryReducer};
 
 use super::{Parsed1, Parsed2};
 
@@ -6,39 +6,24 @@ type Solution = usize;
 pub type Solution1 = Solution;
 pub type Solution2 = Solution;
 
-fn monkey_business<F>(troop: &MonkeyTroop, rounds: usize, worry_reducer: F) -> usize
-where
-    F: Fn(Item) -> Item,
-{
-    let mut troop = troop.clone();
-
-    for _ in 0..rounds {
-        troop.iterate(&worry_reducer);
-    }
-
-    let mut inspections: Vec<_> = troop
-        .monkeys
-        .iter()
-        .map(|monkey| monkey.inspections)
-        .collect();
+fn monkey_business(
+    troop: &MonkeyTroop,
+    rounds: usize,
+    worry_reducer: WorryReducer,
+) -> anyhow::Result<usize> {
+    let mut inspections = troop.inspections(rounds, &worry_reducer)?;
 
     inspections.sort();
 
-    inspections.iter().rev().take(2).product()
+    Ok(inspections.iter().rev().take(2).product())
 }
 
 pub fn solve1(troop: &Parsed1) -> anyhow::Result<Solution1> {
-    Ok(monkey_business(troop, 20, |item| item / 3))
+    monkey_business(troop, 20, WorryReducer::Divide(3))
 }
 
 pub fn solve2(troop: &Parsed2) -> anyhow::Result<Solution2> {
-    let modulo: usize = troop
-        .monkeys
-        .iter()
-        .map(|monkey| monkey.test.divisible_by)
-        .product();
-
-    Ok(monkey_business(troop, 10_000, |item| item % modulo))
+    monkey_business(troop, 10_000, WorryReducer::Modulo(troop.modulus()?))
 }
 
 #[cfg(test)]
@@ -46,6 +31,7 @@ pub mod tests {
     use aoc::Input;
 
     use crate::answer::{parse1, parse2};
+    use crate::core::Predicate;
 
     use super::*;
 
@@ -62,4 +48,42 @@ pub mod tests {
         assert_eq!(solve2(&parse2(INPUT)?)?, 2_713_310_158);
         Ok(())
     }
+
+    #[test]
+    fn test_modulus() -> anyhow::Result<()> {
+        assert_eq!(parse2(INPUT)?.modulus()?, 96_577);
+
+        let mut troop = parse2(INPUT)?;
+        troop.monkeys[0].test.branches[0].predicate = Predicate::DivisibleBy(23 * 19);
+        assert_eq!(troop.modulus()?, 96_577);
+
+        troop.monkeys[0].test.branches[0].predicate = Predicate::GreaterThan(23);
+        assert!(troop.modulus().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_overflow() -> anyhow::Result<()> {
+        let mut troop = parse2(INPUT)?;
+        troop.monkeys[2].items.push_back(usize::MAX / 2);
+
+        // Squaring overflows, but the reduced worry level still fits.
+        assert!(monkey_business(&troop, 1, WorryReducer::Modulo(troop.modulus()?)).is_ok());
+
+        let error = monkey_business(&troop, 1, WorryReducer::Divide(3)).unwrap_err();
+        assert!(format!("{:#}", error).contains("does not fit in an item"));
+
+        let mut troop = parse2(INPUT)?;
+        troop.monkeys[0].operation = "Operation: new = old ^ old".try_into()?;
+        troop.monkeys[0].items.push_back(3_000_000_000);
+
+        let error = monkey_business(&troop, 20, WorryReducer::Divide(3)).unwrap_err();
+        assert!(format!("{:#}", error).contains("does not fit in an item"));
+
+        // Exponents are not reduced, so there is no modulus to keep worry levels small.
+        assert!(troop.modulus().is_err());
+
+        Ok(())
+    }
 }
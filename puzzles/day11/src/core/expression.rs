@@ -0,0 +1,279 @@
+use std::iter::Peekable;
+
+use anyhow::{anyhow, ensure, Context};
+use num::BigUint;
+
+use super::{EvalError, Operator, Value, WorryReducer};
+
+// Bounds the exact values computed while evaluating, including intermediate values that are
+// reduced again later on. Whether the final value fits in an item is up to the worry reducer.
+const MAX_EXACT_BITS: u64 = 1 << 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expression {
+    Value(Value),
+    Binary(Box<Expression>, Operator, Box<Expression>),
+}
+
+impl Expression {
+    pub fn checked_eval(&self, parameter: usize) -> Option<usize> {
+        match self {
+            Self::Value(value) => Some(value.unwrap(parameter)),
+            Self::Binary(lhs, operator, rhs) => {
+                operator.checked_apply(lhs.checked_eval(parameter)?, rhs.checked_eval(parameter)?)
+            }
+        }
+    }
+
+    pub fn eval_big(
+        &self,
+        parameter: &BigUint,
+        worry_reducer: &WorryReducer,
+    ) -> Result<BigUint, EvalError> {
+        match worry_reducer {
+            WorryReducer::Divide(_) => self.eval_exact(parameter),
+            WorryReducer::Modulo(modulus) => self.eval_modulo(parameter, &BigUint::from(*modulus)),
+        }
+    }
+
+    fn eval_exact(&self, parameter: &BigUint) -> Result<BigUint, EvalError> {
+        match self {
+            Self::Value(value) => Ok(value.unwrap_big(parameter)),
+            Self::Binary(lhs, operator, rhs) => operator.apply_big(
+                &lhs.eval_exact(parameter)?,
+                &rhs.eval_exact(parameter)?,
+                MAX_EXACT_BITS,
+            ),
+        }
+    }
+
+    fn eval_modulo(&self, parameter: &BigUint, modulus: &BigUint) -> Result<BigUint, EvalError> {
+        match self {
+            Self::Value(value) => Ok(value.unwrap_big(parameter) % modulus),
+            Self::Binary(lhs, Operator::Power, rhs) => {
+                let exponent = rhs.eval_exact(parameter)?;
+                Ok(lhs
+                    .eval_modulo(parameter, modulus)?
+                    .modpow(&exponent, modulus))
+            }
+            Self::Binary(lhs, operator, rhs) if operator.preserves_remainders() => operator
+                .apply_big(
+                    &lhs.eval_modulo(parameter, modulus)?,
+                    &rhs.eval_modulo(parameter, modulus)?,
+                    MAX_EXACT_BITS,
+                )
+                .map(|value| value % modulus),
+            Self::Binary(..) => self.eval_exact(parameter).map(|value| value % modulus),
+        }
+    }
+
+    pub fn preserves_remainders(&self) -> bool {
+        match self {
+            Self::Value(_) => true,
+            Self::Binary(lhs, Operator::Power, rhs) => {
+                lhs.preserves_remainders() && matches!(**rhs, Self::Value(Value::Const(_)))
+            }
+            Self::Binary(lhs, operator, rhs) => {
+                operator.preserves_remainders()
+                    && lhs.preserves_remainders()
+                    && rhs.preserves_remainders()
+            }
+        }
+    }
+}
+
+fn tokenize(s: &str) -> anyhow::Result<Vec<&str>> {
+    let mut tokens = Vec::new();
+    let mut rest = s.trim_start();
+
+    while let Some(c) = rest.chars().next() {
+        let len = if c.is_ascii_alphanumeric() {
+            rest.find(|c: char| !c.is_ascii_alphanumeric())
+                .unwrap_or(rest.len())
+        } else if rest.starts_with("**") {
+            2
+        } else if "+-*/%^()".contains(c) {
+            1
+        } else {
+            return Err(anyhow!("unexpected character: {:?}", c));
+        };
+
+        let (token, remainder) = rest.split_at(len);
+        tokens.push(token);
+        rest = remainder.trim_start();
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a, I: Iterator<Item = &'a str>> {
+    tokens: Peekable<I>,
+}
+
+impl<'a, I: Iterator<Item = &'a str>> Parser<'a, I> {
+    fn operand(&mut self) -> anyhow::Result<Expression> {
+        match self.tokens.next().context("missing operand")? {
+            "(" => {
+                let expression = self.expression(0)?;
+                ensure!(
+                    self.tokens.next() == Some(")"),
+                    "missing closing parenthesis"
+                );
+                Ok(expression)
+            }
+            token => Value::try_from(token)
+                .with_context(|| format!("invalid value: {token:?}"))
+                .map(Expression::Value),
+        }
+    }
+
+    // Precedence climbing: keep folding operators that bind at least as tightly as the caller.
+    fn expression(&mut self, min_precedence: u8) -> anyhow::Result<Expression> {
+        let mut lhs = self.operand()?;
+
+        while let Some(operator) = self
+            .tokens
+            .peek()
+            .and_then(|&token| Operator::try_from(token).ok())
+            .filter(|operator| operator.precedence() >= min_precedence)
+        {
+            self.tokens.next();
+
+            let next = if operator.is_right_associative() {
+                operator.precedence()
+            } else {
+                operator.precedence() + 1
+            };
+
+            let rhs = self.expression(next)?;
+            lhs = Expression::Binary(Box::new(lhs), operator, Box::new(rhs));
+        }
+
+        Ok(lhs)
+    }
+}
+
+impl TryFrom<&str> for Expression {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let tokens = tokenize(s)?;
+
+        let mut parser = Parser {
+            tokens: tokens.into_iter().peekable(),
+        };
+
+        let expression = parser.expression(0)?;
+
+        if let Some(token) = parser.tokens.next() {
+            return Err(anyhow!("unexpected token: {:?}", token));
+        }
+
+        Ok(expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(s: &str, old: usize) -> Option<usize> {
+        Expression::try_from(s).unwrap().checked_eval(old)
+    }
+
+    #[test]
+    fn test_eval() {
+        assert_eq!(eval("old * 19", 2), Some(38));
+        assert_eq!(eval("old * old", 7), Some(49));
+        assert_eq!(eval("old + 3 * 2", 1), Some(7));
+        assert_eq!(eval("(old + 3) * 2", 1), Some(8));
+        assert_eq!(eval("old - 2 - 1", 10), Some(7));
+        assert_eq!(eval("2 ^ 3 ^ 2", 0), Some(512));
+        assert_eq!(eval("old ** 2 % 5", 4), Some(1));
+        assert_eq!(eval("old / 3 + old % 3", 10), Some(4));
+        assert_eq!(eval("old - 1", 0), None);
+        assert_eq!(eval("old / 0", 1), None);
+        assert_eq!(eval("old * old", usize::MAX), None);
+    }
+
+    #[test]
+    fn test_eval_big() {
+        let eval = |s: &str, old: usize, worry_reducer: WorryReducer| {
+            Expression::try_from(s)
+                .unwrap()
+                .eval_big(&BigUint::from(old), &worry_reducer)
+        };
+
+        let big = BigUint::from(usize::MAX);
+        assert_eq!(
+            eval("old * old + 1", usize::MAX, WorryReducer::Divide(1)),
+            Ok(&big * &big + 1u32)
+        );
+        assert_eq!(
+            eval("1 - old", usize::MAX, WorryReducer::Divide(1)),
+            Err(EvalError::Undefined)
+        );
+
+        assert_eq!(
+            eval("old ^ old", 3_000_000_000, WorryReducer::Divide(3)),
+            Err(EvalError::TooLarge)
+        );
+        assert_eq!(
+            eval("old ^ 2", 1 << 32, WorryReducer::Divide(3)),
+            Ok(BigUint::from(1u128 << 64))
+        );
+        assert_eq!(
+            eval("old ^ 3", 1 << 32, WorryReducer::Divide(3)),
+            Ok(BigUint::from(1u128 << 96))
+        );
+        assert_eq!(
+            eval("old ^ 3000", 1 << 32, WorryReducer::Divide(3)),
+            Err(EvalError::TooLarge)
+        );
+        assert_eq!(
+            eval("old ^ 2 % 7", 1 << 40, WorryReducer::Divide(3)),
+            Ok(BigUint::from(4u32))
+        );
+        assert_eq!(
+            eval("old ^ 2 % 7", 1 << 40, WorryReducer::Divide(3)),
+            eval("old * old % 7", 1 << 40, WorryReducer::Divide(3))
+        );
+
+        let modulus = 96_577;
+        let expected = BigUint::from(3_000_000_000usize)
+            .modpow(&BigUint::from(3_000_000_000usize), &BigUint::from(modulus))
+            + 1u32;
+        assert_eq!(
+            eval(
+                "old ^ old + 1",
+                3_000_000_000,
+                WorryReducer::Modulo(modulus)
+            ),
+            Ok(expected)
+        );
+        assert_eq!(
+            eval("(old * old - 1) % 7", 1 << 40, WorryReducer::Modulo(2)),
+            Ok(BigUint::from(((1u128 << 80) - 1) % 7 % 2))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in ["", "old +", "(old", "old)", "old & 2", "old 2", "new"] {
+            assert!(Expression::try_from(s).is_err(), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn test_preserves_remainders() {
+        assert!(Expression::try_from("(old + 3) * old ^ 2")
+            .unwrap()
+            .preserves_remainders());
+        assert!(!Expression::try_from("old / 3")
+            .unwrap()
+            .preserves_remainders());
+        assert!(!Expression::try_from("2 ^ old")
+            .unwrap()
+            .preserves_remainders());
+    }
+}
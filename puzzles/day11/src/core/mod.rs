@@ -6,6 +6,9 @@ pub use monkey::*;
 pub mod operator;
 pub use operator::*;
 
+pub mod expression;
+pub use expression::*;
+
 pub mod operation;
 pub use operation::*;
 
@@ -14,3 +17,9 @@ pub use value::*;
 
 pub mod test;
 pub use test::*;
+
+pub mod worry;
+pub use worry::*;
+
+pub mod trajectory;
+pub use trajectory::*;
@@ -1,11 +1,12 @@
 use std::collections::VecDeque;
 use std::convert::TryFrom;
 
-use anyhow::Context;
+use anyhow::{anyhow, bail, ensure, Context};
+use num::BigUint;
 
 use crate::core::parse::ensure_prefix;
 
-use super::{Operation, Test};
+use super::{EvalError, Operation, Test, WorryReducer};
 
 pub type Item = usize;
 
@@ -23,6 +24,34 @@ impl Monkey {
         self.inspections += 1;
         Some(item)
     }
+
+    // Worry levels that overflow during the operation are recomputed exactly, and only rejected
+    // when they are still too large after being reduced.
+    pub fn inspect(&self, item: Item, worry_reducer: &WorryReducer) -> anyhow::Result<Item> {
+        match self.operation.checked_eval(item) {
+            Some(item) => Ok(worry_reducer.reduce(item)),
+            None => {
+                let worry = match self.operation.eval_big(&BigUint::from(item), worry_reducer) {
+                    Ok(worry) => worry,
+                    Err(EvalError::Undefined) => {
+                        bail!("operation is undefined for worry level {}", item)
+                    }
+                    Err(EvalError::TooLarge) => bail!(
+                        "worry level for {} does not fit in an item after {:?}",
+                        item,
+                        worry_reducer
+                    ),
+                };
+                worry_reducer.reduce_big(&worry).ok_or_else(|| {
+                    anyhow!(
+                        "worry level {} does not fit in an item after {:?}",
+                        worry,
+                        worry_reducer
+                    )
+                })
+            }
+        }
+    }
 }
 
 impl TryFrom<&str> for Monkey {
@@ -45,7 +74,7 @@ impl TryFrom<&str> for Monkey {
         }
 
         fn parse_test<'a>(iter: &mut impl Iterator<Item = &'a str>) -> anyhow::Result<Test> {
-            let s = iter.take(3).collect::<Vec<_>>().join("\n");
+            let s = iter.collect::<Vec<_>>().join("\n");
             Test::try_from(s.as_str()).with_context(|| format!("invalid test: {s:?}"))
         }
 
@@ -70,24 +99,44 @@ pub struct MonkeyTroop {
 }
 
 impl MonkeyTroop {
-    fn throw_items<F>(&mut self, from: usize, worry_reducer: &F)
-    where
-        F: Fn(Item) -> Item,
-    {
+    pub fn modulus(&self) -> anyhow::Result<Item> {
+        for (i, monkey) in self.monkeys.iter().enumerate() {
+            ensure!(
+                monkey.test.is_divisibility() && monkey.operation.expression.preserves_remainders(),
+                "worry levels of monkey number {} cannot be reduced by a modulus",
+                i + 1
+            );
+        }
+
+        Ok(self
+            .monkeys
+            .iter()
+            .flat_map(|monkey| monkey.test.divisors())
+            .fold(1, num::integer::lcm))
+    }
+
+    fn throw_items(&mut self, from: usize, worry_reducer: &WorryReducer) -> anyhow::Result<()> {
         while let Some(item) = self.monkeys[from].throw_item() {
-            let item = worry_reducer(self.monkeys[from].operation.eval(item));
+            let item = self.monkeys[from]
+                .inspect(item, worry_reducer)
+                .with_context(|| format!("monkey number {}", from + 1))?;
             let to = self.monkeys[from].test.eval(item);
-            self.monkeys[to].items.push_back(item);
+            self.monkeys
+                .get_mut(to)
+                .with_context(|| format!("monkey number {} does not exist", to + 1))?
+                .items
+                .push_back(item);
         }
+
+        Ok(())
     }
 
-    pub fn iterate<F>(&mut self, worry_reducer: &F)
-    where
-        F: Fn(Item) -> Item,
-    {
+    pub fn iterate(&mut self, worry_reducer: &WorryReducer) -> anyhow::Result<()> {
         for i in 0..self.monkeys.len() {
-            self.throw_items(i, worry_reducer);
+            self.throw_items(i, worry_reducer)?;
         }
+
+        Ok(())
     }
 }
 
@@ -1,29 +1,30 @@
 use std::convert::TryFrom;
 
 use anyhow::{ensure, Context};
+use num::BigUint;
 
 use crate::core::parse::ensure_prefix;
 
-use super::{Operator, Value};
+use super::{EvalError, Expression, WorryReducer};
 
 const NEW: &str = "new";
 
 #[derive(Debug, Clone)]
 pub struct Operation {
-    operand1: Value,
-    operand2: Value,
-    operator: Operator,
+    pub expression: Expression,
 }
 
 impl Operation {
-    pub fn eval(&self, parameter: usize) -> usize {
-        let value1 = self.operand1.unwrap(parameter);
-        let value2 = self.operand2.unwrap(parameter);
+    pub fn checked_eval(&self, parameter: usize) -> Option<usize> {
+        self.expression.checked_eval(parameter)
+    }
 
-        match self.operator {
-            Operator::Add => value1 + value2,
-            Operator::Multiply => value1 * value2,
-        }
+    pub fn eval_big(
+        &self,
+        parameter: &BigUint,
+        worry_reducer: &WorryReducer,
+    ) -> Result<BigUint, EvalError> {
+        self.expression.eval_big(parameter, worry_reducer)
     }
 }
 
@@ -31,11 +32,6 @@ impl TryFrom<&str> for Operation {
     type Error = anyhow::Error;
 
     fn try_from(s: &str) -> anyhow::Result<Operation> {
-        fn parse_operand(s: &str) -> anyhow::Result<Value> {
-            s.try_into()
-                .with_context(|| format!("invalid value: {s:?}"))
-        }
-
         let s = ensure_prefix(s, "Operation:")?;
 
         let mut split = s.trim().splitn(2, '=');
@@ -49,16 +45,10 @@ impl TryFrom<&str> for Operation {
             NEW
         );
 
-        let mut words = rhs.split_whitespace();
-
-        let operand1 = parse_operand(words.next().context("missing first operand")?)?;
-        let operator = words.next().context("missing operator")?.try_into()?;
-        let operand2 = parse_operand(words.next().context("missing second operand")?)?;
+        let expression = rhs
+            .try_into()
+            .with_context(|| format!("invalid expression: {rhs:?}"))?;
 
-        Ok(Self {
-            operand1,
-            operator,
-            operand2,
-        })
+        Ok(Self { expression })
     }
 }
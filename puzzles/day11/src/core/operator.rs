@@ -1,9 +1,74 @@
 use anyhow::anyhow;
+use num::{BigUint, CheckedSub, One, ToPrimitive, Zero};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvalError {
+    Undefined,
+    TooLarge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Operator {
     Add,
+    Subtract,
     Multiply,
+    Divide,
+    Remainder,
+    Power,
+}
+
+impl Operator {
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Self::Add | Self::Subtract => 1,
+            Self::Multiply | Self::Divide | Self::Remainder => 2,
+            Self::Power => 3,
+        }
+    }
+
+    pub fn is_right_associative(&self) -> bool {
+        matches!(self, Self::Power)
+    }
+
+    // Only these operators give the same remainder when their operands are reduced first.
+    pub fn preserves_remainders(&self) -> bool {
+        matches!(self, Self::Add | Self::Multiply | Self::Power)
+    }
+
+    pub fn checked_apply(&self, a: usize, b: usize) -> Option<usize> {
+        match self {
+            Self::Add => a.checked_add(b),
+            Self::Subtract => a.checked_sub(b),
+            Self::Multiply => a.checked_mul(b),
+            Self::Divide => a.checked_div(b),
+            Self::Remainder => a.checked_rem(b),
+            Self::Power => a.checked_pow(b.try_into().ok()?),
+        }
+    }
+
+    // Powers are only computed when the result is known to have at most `max_bits` bits.
+    pub fn apply_big(&self, a: &BigUint, b: &BigUint, max_bits: u64) -> Result<BigUint, EvalError> {
+        match self {
+            Self::Add => Ok(a + b),
+            Self::Subtract => a.checked_sub(b).ok_or(EvalError::Undefined),
+            Self::Multiply => Ok(a * b),
+            Self::Divide if b.is_zero() => Err(EvalError::Undefined),
+            Self::Divide => Ok(a / b),
+            Self::Remainder if b.is_zero() => Err(EvalError::Undefined),
+            Self::Remainder => Ok(a % b),
+            Self::Power if b.is_zero() => Ok(BigUint::one()),
+            Self::Power if a <= &BigUint::one() => Ok(a.clone()),
+            Self::Power => {
+                // The result has more than (bits(a) - 1) * b bits.
+                let exponent = b
+                    .to_u64()
+                    .filter(|&b| (a.bits() - 1).saturating_mul(b) < max_bits)
+                    .and_then(|b| u32::try_from(b).ok())
+                    .ok_or(EvalError::TooLarge)?;
+                Ok(a.pow(exponent))
+            }
+        }
+    }
 }
 
 impl TryFrom<&str> for Operator {
@@ -12,7 +77,11 @@ impl TryFrom<&str> for Operator {
     fn try_from(s: &str) -> Result<Self, Self::Error> {
         match s {
             "+" => Ok(Self::Add),
+            "-" => Ok(Self::Subtract),
             "*" => Ok(Self::Multiply),
+            "/" => Ok(Self::Divide),
+            "%" => Ok(Self::Remainder),
+            "^" | "**" => Ok(Self::Power),
             _ => Err(anyhow!("invalid operator: {:?}", s)),
         }
     }
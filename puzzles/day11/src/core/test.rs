@@ -1,61 +1,227 @@
 use std::convert::TryFrom;
 
-use anyhow::Context;
+use anyhow::{anyhow, ensure, Context};
 
 use crate::core::parse::ensure_prefix;
 
-#[derive(Debug, Clone)]
-pub struct Test {
-    pub divisible_by: usize,
-    pub if_true: usize,
-    pub if_false: usize,
+use super::Item;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Predicate {
+    DivisibleBy(Item),
+    GreaterThan(Item),
+    EqualTo(Item),
 }
 
-impl Test {
-    pub fn eval(&self, value: usize) -> usize {
-        if value % self.divisible_by == 0 {
-            self.if_true
+impl Predicate {
+    pub fn eval(&self, value: Item) -> bool {
+        match self {
+            Self::DivisibleBy(divisor) => value.is_multiple_of(*divisor),
+            Self::GreaterThan(bound) => value > *bound,
+            Self::EqualTo(other) => value == *other,
+        }
+    }
+}
+
+impl TryFrom<&str> for Predicate {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        fn parse_int(s: &str) -> anyhow::Result<Item> {
+            s.trim()
+                .parse()
+                .with_context(|| format!("invalid integer: {s:?}"))
+        }
+
+        let s = s.trim();
+
+        if let Some(divisor) = s.strip_prefix("divisible by") {
+            let divisor = parse_int(divisor)?;
+            ensure!(divisor > 0, "divisor must be positive");
+            Ok(Self::DivisibleBy(divisor))
+        } else if let Some(bound) = s.strip_prefix("greater than") {
+            Ok(Self::GreaterThan(parse_int(bound)?))
+        } else if let Some(other) = s.strip_prefix("equal to") {
+            Ok(Self::EqualTo(parse_int(other)?))
         } else {
-            self.if_false
+            Err(anyhow!("invalid condition: {s:?}"))
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Branch {
+    pub predicate: Predicate,
+    pub target: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Test {
+    pub branches: Vec<Branch>,
+    pub otherwise: usize,
+}
+
+impl Test {
+    pub fn eval(&self, value: Item) -> usize {
+        self.branches
+            .iter()
+            .find(|branch| branch.predicate.eval(value))
+            .map_or(self.otherwise, |branch| branch.target)
+    }
+
+    pub fn divisors(&self) -> impl Iterator<Item = Item> + '_ {
+        self.branches
+            .iter()
+            .filter_map(|branch| match branch.predicate {
+                Predicate::DivisibleBy(divisor) => Some(divisor),
+                _ => None,
+            })
+    }
+
+    pub fn is_divisibility(&self) -> bool {
+        self.branches
+            .iter()
+            .all(|branch| matches!(branch.predicate, Predicate::DivisibleBy(_)))
+    }
+}
+
+fn parse_target(s: &str, prefix: &str) -> anyhow::Result<usize> {
+    let target = ensure_prefix(s, prefix)?;
+    target
+        .parse()
+        .with_context(|| format!("invalid target: {target:?}"))
+}
+
+// Tests either take the original form, with one condition and true/false branches, or list any
+// number of "If <condition>: throw to monkey N" branches followed by an "Otherwise" branch.
 impl TryFrom<&str> for Test {
     type Error = anyhow::Error;
 
     fn try_from(s: &str) -> Result<Self, Self::Error> {
         let mut lines = s.lines();
 
-        let divisible_by =
-            ensure_prefix(lines.next().context("missing test")?, "Test: divisible by")?;
+        let condition = ensure_prefix(lines.next().context("missing test")?, "Test:")?;
+
+        let test = if condition.is_empty() {
+            let mut branches = Vec::new();
+            let mut otherwise = None;
+
+            for (i, line) in lines.enumerate() {
+                ensure!(otherwise.is_none(), "unexpected branch after otherwise");
+
+                let line = line.trim();
+
+                if line.starts_with("Otherwise:") {
+                    otherwise = Some(parse_target(line, "Otherwise: throw to monkey")?);
+                    continue;
+                }
+
+                let (predicate, target) = ensure_prefix(line, "If")?
+                    .split_once(':')
+                    .with_context(|| format!("invalid branch: {line:?}"))?;
+
+                branches.push(Branch {
+                    predicate: predicate
+                        .try_into()
+                        .with_context(|| format!("branch number {}", i + 1))?,
+                    target: parse_target(target, "throw to monkey")?,
+                });
+            }
+
+            Self {
+                branches,
+                otherwise: otherwise.context("missing otherwise branch")?,
+            }
+        } else {
+            let predicate = condition.try_into()?;
+
+            let if_true = parse_target(
+                lines.next().context("missing true branch")?,
+                "If true: throw to monkey",
+            )?;
+
+            let if_false = parse_target(
+                lines.next().context("missing false branch")?,
+                "If false: throw to monkey",
+            )?;
+
+            if let Some(line) = lines.next() {
+                return Err(anyhow!("unexpected line: {line:?}"));
+            }
+
+            Self {
+                branches: vec![Branch {
+                    predicate,
+                    target: if_true,
+                }],
+                otherwise: if_false,
+            }
+        };
+
+        Ok(test)
+    }
+}
 
-        let divisible_by = divisible_by
-            .parse()
-            .with_context(|| format!("invalid condition: {divisible_by:?}"))?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let if_true = ensure_prefix(
-            lines.next().context("missing true branch")?,
-            "If true: throw to monkey",
+    #[test]
+    fn test_original() -> anyhow::Result<()> {
+        let test = Test::try_from(
+            "\
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3",
         )?;
 
-        let if_true = if_true
-            .parse()
-            .with_context(|| format!("invalid true branch: {if_true:?}"))?;
+        assert_eq!(
+            test,
+            Test {
+                branches: vec![Branch {
+                    predicate: Predicate::DivisibleBy(23),
+                    target: 2
+                }],
+                otherwise: 3
+            }
+        );
+        assert_eq!(test.eval(46), 2);
+        assert_eq!(test.eval(47), 3);
+
+        Ok(())
+    }
 
-        let if_false = ensure_prefix(
-            lines.next().context("missing false branch")?,
-            "If false: throw to monkey",
+    #[test]
+    fn test_branches() -> anyhow::Result<()> {
+        let test = Test::try_from(
+            "\
+  Test:
+    If equal to 7: throw to monkey 4
+    If divisible by 5: throw to monkey 1
+    If greater than 100: throw to monkey 2
+    Otherwise: throw to monkey 0",
         )?;
 
-        let if_false = if_false
-            .parse()
-            .with_context(|| format!("invalid false branch: {if_false:?}"))?;
+        assert_eq!(test.eval(7), 4);
+        assert_eq!(test.eval(200), 1);
+        assert_eq!(test.eval(201), 2);
+        assert_eq!(test.eval(3), 0);
+        assert_eq!(test.divisors().collect::<Vec<_>>(), [5]);
+        assert!(!test.is_divisibility());
 
-        Ok(Self {
-            divisible_by,
-            if_true,
-            if_false,
-        })
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid() {
+        for s in [
+            "Test: divisible by 0\nIf true: throw to monkey 1\nIf false: throw to monkey 2",
+            "Test: odd\nIf true: throw to monkey 1\nIf false: throw to monkey 2",
+            "Test: divisible by 2\nIf true: throw to monkey 1",
+            "Test:\nIf equal to 1: throw to monkey 1",
+            "Test:\nOtherwise: throw to monkey 1\nIf equal to 1: throw to monkey 1",
+        ] {
+            assert!(Test::try_from(s).is_err(), "{:?}", s);
+        }
     }
 }
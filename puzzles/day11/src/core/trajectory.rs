@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use anyhow::{ensure, Context};
+
+use super::{Item, MonkeyTroop, WorryReducer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+// An item is only ever affected by the monkey holding it, so its path through the troop can be
+// followed on its own. States are taken at the start of each round: an item that is thrown to a
+// later monkey is inspected again in the same round, while one thrown to an earlier monkey waits
+// for the next round. Since every state marks a round boundary, the round parity is not needed to
+// tell states apart, and adding it would only double the length of some cycles. Once a state
+// repeats, the rest of the path is periodic.
+#[derive(Debug, Clone)]
+pub struct Trajectory {
+    monkeys: usize,
+    // Every monkey that inspects the item, in order, and where each round starts among them.
+    inspectors: Vec<usize>,
+    round_starts: Vec<usize>,
+    pub cycle: Option<Cycle>,
+}
+
+impl Trajectory {
+    pub fn trace(
+        troop: &MonkeyTroop,
+        monkey: usize,
+        item: Item,
+        rounds: usize,
+        worry_reducer: &WorryReducer,
+    ) -> anyhow::Result<Self> {
+        let mut trajectory = Self {
+            monkeys: troop.monkeys.len(),
+            inspectors: Vec::new(),
+            round_starts: vec![0],
+            cycle: None,
+        };
+        let mut seen = HashMap::new();
+        let mut state = (monkey, item);
+
+        for round in 0..rounds {
+            if let Some(start) = seen.insert(state, round) {
+                trajectory.cycle = Some(Cycle {
+                    start,
+                    len: round - start,
+                });
+                break;
+            }
+
+            state = trajectory
+                .round(troop, state, worry_reducer)
+                .with_context(|| format!("round number {}", round + 1))?;
+            trajectory.round_starts.push(trajectory.inspectors.len());
+        }
+
+        Ok(trajectory)
+    }
+
+    fn round(
+        &mut self,
+        troop: &MonkeyTroop,
+        (mut from, mut item): (usize, Item),
+        worry_reducer: &WorryReducer,
+    ) -> anyhow::Result<(usize, Item)> {
+        loop {
+            let monkey = &troop.monkeys[from];
+
+            item = monkey
+                .inspect(item, worry_reducer)
+                .with_context(|| format!("monkey number {}", from + 1))?;
+            self.inspectors.push(from);
+
+            let to = monkey.test.eval(item);
+
+            ensure!(
+                to < troop.monkeys.len(),
+                "monkey number {} does not exist",
+                to + 1
+            );
+            ensure!(
+                to != from,
+                "monkey number {} throws an item to itself",
+                from + 1
+            );
+
+            if to < from {
+                return Ok((to, item));
+            }
+
+            from = to;
+        }
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.round_starts.len() - 1
+    }
+
+    fn count(&self, rounds: Range<usize>) -> Vec<usize> {
+        let mut counts = vec![0; self.monkeys];
+
+        for &monkey in
+            &self.inspectors[self.round_starts[rounds.start]..self.round_starts[rounds.end]]
+        {
+            counts[monkey] += 1;
+        }
+
+        counts
+    }
+
+    pub fn inspections(&self, rounds: usize) -> Option<Vec<usize>> {
+        if rounds <= self.rounds() {
+            return Some(self.count(0..rounds));
+        }
+
+        let Cycle { start, len } = self.cycle?;
+        let (cycles, offset) = ((rounds - start) / len, (rounds - start) % len);
+
+        let partial = self.count(0..start + offset);
+        let cycle = self.count(start..start + len);
+
+        Some(
+            partial
+                .iter()
+                .zip(cycle)
+                .map(|(partial, cycle)| partial + cycles * cycle)
+                .collect(),
+        )
+    }
+}
+
+impl MonkeyTroop {
+    pub fn trajectories(
+        &self,
+        rounds: usize,
+        worry_reducer: &WorryReducer,
+    ) -> anyhow::Result<Vec<Trajectory>> {
+        self.monkeys
+            .iter()
+            .enumerate()
+            .flat_map(|(i, monkey)| monkey.items.iter().map(move |&item| (i, item)))
+            .map(|(i, item)| {
+                Trajectory::trace(self, i, item, rounds, worry_reducer)
+                    .with_context(|| format!("item {} of monkey number {}", item, i + 1))
+            })
+            .collect()
+    }
+
+    pub fn inspections(
+        &self,
+        rounds: usize,
+        worry_reducer: &WorryReducer,
+    ) -> anyhow::Result<Vec<usize>> {
+        let mut inspections: Vec<_> = self
+            .monkeys
+            .iter()
+            .map(|monkey| monkey.inspections)
+            .collect();
+
+        for trajectory in self.trajectories(rounds, worry_reducer)? {
+            let counts = trajectory
+                .inspections(rounds)
+                .context("trajectory ends before the last round")?;
+
+            for (total, count) in inspections.iter_mut().zip(counts) {
+                *total += count;
+            }
+        }
+
+        Ok(inspections)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = include_str!("../../input-test");
+
+    fn simulate(
+        troop: &MonkeyTroop,
+        rounds: usize,
+        worry_reducer: &WorryReducer,
+    ) -> anyhow::Result<Vec<usize>> {
+        let mut troop = troop.clone();
+
+        for _ in 0..rounds {
+            troop.iterate(worry_reducer)?;
+        }
+
+        Ok(troop
+            .monkeys
+            .iter()
+            .map(|monkey| monkey.inspections)
+            .collect())
+    }
+
+    #[test]
+    fn test_cycle() -> anyhow::Result<()> {
+        let troop = MonkeyTroop::try_from(INPUT)?;
+        let worry_reducer = WorryReducer::Modulo(troop.modulus()?);
+
+        for trajectory in troop.trajectories(usize::MAX, &worry_reducer)? {
+            let cycle = trajectory.cycle.unwrap();
+            assert_eq!(trajectory.rounds(), cycle.start + cycle.len);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_inspections() -> anyhow::Result<()> {
+        let troop = MonkeyTroop::try_from(INPUT)?;
+        let worry_reducer = WorryReducer::Modulo(troop.modulus()?);
+        let trajectories = troop.trajectories(usize::MAX, &worry_reducer)?;
+
+        let longest = trajectories.iter().map(Trajectory::rounds).max().unwrap();
+
+        for rounds in [0, 1, 20, 1_000, longest, longest + 1, 3 * longest + 7] {
+            assert_eq!(
+                troop.inspections(rounds, &worry_reducer)?,
+                simulate(&troop, rounds, &worry_reducer)?,
+                "after {} rounds",
+                rounds
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_divide() -> anyhow::Result<()> {
+        let troop = MonkeyTroop::try_from(INPUT)?;
+        let worry_reducer = WorryReducer::Divide(3);
+
+        assert_eq!(
+            troop.inspections(20, &worry_reducer)?,
+            simulate(&troop, 20, &worry_reducer)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_huge() -> anyhow::Result<()> {
+        let troop = MonkeyTroop::try_from(INPUT)?;
+        let worry_reducer = WorryReducer::Modulo(troop.modulus()?);
+
+        let inspections = troop.inspections(1_000_000_000_000, &worry_reducer)?;
+        assert_eq!(inspections.len(), 4);
+        assert!(inspections.iter().all(|&count| count > 1_000_000_000));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_self_throw() -> anyhow::Result<()> {
+        let mut troop = MonkeyTroop::try_from(INPUT)?;
+        troop.monkeys[0].test.otherwise = 0;
+
+        let error = troop.inspections(20, &WorryReducer::Divide(3)).unwrap_err();
+        assert!(format!("{:#}", error).contains("throws an item to itself"));
+
+        Ok(())
+    }
+}
@@ -1,8 +1,9 @@
 use anyhow::Context;
+use num::BigUint;
 
 const OLD: &str = "old";
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Value {
     Parameter,
     Const(usize),
@@ -15,6 +16,13 @@ impl Value {
             Value::Const(value) => *value,
         }
     }
+
+    pub fn unwrap_big(&self, parameter: &BigUint) -> BigUint {
+        match self {
+            Value::Parameter => parameter.clone(),
+            Value::Const(value) => BigUint::from(*value),
+        }
+    }
 }
 
 impl TryFrom<&str> for Value {
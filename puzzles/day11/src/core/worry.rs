@@ -0,0 +1,26 @@
+use num::{BigUint, ToPrimitive};
+
+use super::Item;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorryReducer {
+    Divide(Item),
+    Modulo(Item),
+}
+
+impl WorryReducer {
+    pub fn reduce(&self, item: Item) -> Item {
+        match self {
+            Self::Divide(divisor) => item / divisor,
+            Self::Modulo(modulus) => item % modulus,
+        }
+    }
+
+    pub fn reduce_big(&self, item: &BigUint) -> Option<Item> {
+        match self {
+            Self::Divide(divisor) => item / divisor,
+            Self::Modulo(modulus) => item % modulus,
+        }
+        .to_usize()
+    }
+}